@@ -19,7 +19,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let docx_content = include_bytes!("/home/x/Desktop/常用sh/工具/docx-tool/output.docx");
 
     let mut docx: Docx = read_docx(docx_content)?;
-    let store = DocxDb::new(&mut docx.document);
+    let store = DocxDb::new(&mut docx);
     let mut glue: Glue<DocxDb> = Glue::new(store);
 
     // let result = glue
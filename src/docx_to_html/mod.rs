@@ -1,13 +1,178 @@
 use docx_rs::{
-    BuildXML, Document, DocumentChild, Docx, Justification, TableAlignmentType, TableChild,
-    WidthType, read_docx,
+    DocumentChild, Docx, Paragraph, Table, TableCell, TableCellContent, TableChild, TableRowChild,
 };
 
+use crate::docx_util::read::read_docx_checked;
+use crate::sql_parser::cell::paragraph_children_text;
+
+// 转义html中的特殊字符
+pub fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// 把docx正文转为html，只保留段落和表格的文本结构
+pub fn docx_to_html(docx: &Docx) -> String {
+    let mut html = String::from("<html><head><meta charset=\"utf-8\"></head><body>\n");
+    for child in &docx.document.children {
+        match child {
+            DocumentChild::Paragraph(paragraph) => html.push_str(&paragraph_to_html(paragraph)),
+            DocumentChild::Table(table) => html.push_str(&table_to_html(table)),
+            _ => {}
+        }
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+// 提取docx正文的纯文本，段落换行，单元格之间使用制表符
+pub fn extract_text(docx: &Docx) -> String {
+    let mut lines: Vec<String> = vec![];
+    for child in &docx.document.children {
+        match child {
+            DocumentChild::Paragraph(paragraph) => {
+                lines.push(paragraph_children_text(&paragraph.children))
+            }
+            DocumentChild::Table(table) => lines.push(table_to_text(table)),
+            _ => {}
+        }
+    }
+    lines.join("\n")
+}
+
+// 按输出文件的扩展名把docx转为html、markdown或纯文本，其他扩展名返回None
+pub fn convert_docx(
+    docx_bytes: &[u8],
+    extension: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let convert: fn(&Docx) -> String = match extension.to_lowercase().as_str() {
+        "html" | "htm" => docx_to_html,
+        "md" => docx_to_markdown,
+        "txt" => extract_text,
+        _ => return Ok(None),
+    };
+    Ok(Some(convert(&read_docx_checked(docx_bytes)?)))
+}
+
+// 把docx正文转为markdown，表格的第一行作为表头
+pub fn docx_to_markdown(docx: &Docx) -> String {
+    let mut blocks: Vec<String> = vec![];
+    for child in &docx.document.children {
+        match child {
+            DocumentChild::Paragraph(paragraph) => {
+                blocks.push(paragraph_children_text(&paragraph.children))
+            }
+            DocumentChild::Table(table) => blocks.push(table_to_markdown(table)),
+            _ => {}
+        }
+    }
+    blocks.join("\n\n") + "\n"
+}
+
+fn paragraph_to_html(paragraph: &Paragraph) -> String {
+    format!(
+        "<p>{}</p>\n",
+        escape_html(&paragraph_children_text(&paragraph.children))
+    )
+}
+
+fn table_to_html(table: &Table) -> String {
+    let mut html = String::from("<table border=\"1\">\n");
+    for TableChild::TableRow(row) in &table.rows {
+        html.push_str("<tr>");
+        for TableRowChild::TableCell(cell) in &row.cells {
+            html.push_str("<td>");
+            for content in &cell.children {
+                match content {
+                    TableCellContent::Paragraph(paragraph) => {
+                        html.push_str(&paragraph_to_html(paragraph))
+                    }
+                    TableCellContent::Table(table) => html.push_str(&table_to_html(table)),
+                    _ => {}
+                }
+            }
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn table_to_text(table: &Table) -> String {
+    let mut lines: Vec<String> = vec![];
+    for TableChild::TableRow(row) in &table.rows {
+        let cells = row
+            .cells
+            .iter()
+            .map(|TableRowChild::TableCell(cell)| cell_to_text(cell, " "))
+            .collect::<Vec<String>>();
+        lines.push(cells.join("\t"));
+    }
+    lines.join("\n")
+}
+
+// 单元格的文本，段落之间使用separator连接
+fn cell_to_text(cell: &TableCell, separator: &str) -> String {
+    cell.children
+        .iter()
+        .map(|content| match content {
+            TableCellContent::Paragraph(paragraph) => paragraph_children_text(&paragraph.children),
+            TableCellContent::Table(table) => table_to_text(table),
+            _ => "".to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+fn table_to_markdown(table: &Table) -> String {
+    let rows = table
+        .rows
+        .iter()
+        .map(|TableChild::TableRow(row)| {
+            row.cells
+                .iter()
+                .map(|TableRowChild::TableCell(cell)| {
+                    // 单元格内不能换行，也不能出现未转义的|
+                    cell_to_text(cell, "\n")
+                        .replace('|', "\\|")
+                        .replace('\n', "<br>")
+                })
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<Vec<String>>>();
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return "".to_string();
+    }
+
+    let mut lines: Vec<String> = vec![];
+    for (index, row) in rows.iter().enumerate() {
+        let mut cells = row.clone();
+        cells.resize(columns, "".to_string());
+        lines.push(format!("| {} |", cells.join(" | ")));
+        if index == 0 {
+            lines.push(format!("|{}", " --- |".repeat(columns)));
+        }
+    }
+    lines.join("\n")
+}
+
 #[test]
 pub fn to_json() {
     // 读取docx
     let docx_content = include_bytes!("../../asset/测试.docx");
-    let mut docx: Docx = read_docx(docx_content).unwrap();
+    let docx: Docx = docx_rs::read_docx(docx_content).unwrap();
 
     // 遍历
     for child in docx.document.children {
@@ -19,3 +184,89 @@ pub fn to_json() {
         }
     }
 }
+
+#[test]
+fn escape_special_characters() {
+    assert_eq!(
+        escape_html("<a href=\"x\">Tom & Jerry's</a>"),
+        "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+    );
+    // 中文标点不转义
+    assert_eq!(escape_html("“你好”，《测试》！"), "“你好”，《测试》！");
+}
+
+#[test]
+fn html_escapes_and_text_keeps_characters() {
+    use docx_rs::{Run, TableCell, TableRow};
+
+    let text = "a < b && c > \"d\"，《e》";
+    let built = Docx::new()
+        .add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)))
+        .add_table(Table::new(vec![TableRow::new(vec![
+            TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("x&y"))),
+            TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("<z>"))),
+        ])]));
+    // add_text会预先转义，打包后重新读取，与实际读到的文档一致
+    let mut buf = std::io::Cursor::new(vec![]);
+    built.build().pack(&mut buf).unwrap();
+    let docx = docx_rs::read_docx(buf.get_ref()).unwrap();
+
+    let html = docx_to_html(&docx);
+    assert!(html.contains("<p>a &lt; b &amp;&amp; c &gt; &quot;d&quot;，《e》</p>"));
+    assert!(html.contains("<td><p>x&amp;y</p>\n</td><td><p>&lt;z&gt;</p>\n</td>"));
+
+    assert_eq!(extract_text(&docx), format!("{text}\nx&y\t<z>"));
+}
+
+#[test]
+fn markdown_table_uses_first_row_as_header() {
+    use docx_rs::{Run, TableRow};
+
+    let cell = |text: &str| {
+        TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)))
+    };
+    let docx = Docx::new()
+        .add_paragraph(Paragraph::new().add_run(Run::new().add_text("标题")))
+        .add_table(Table::new(vec![
+            TableRow::new(vec![cell("名称"), cell("说明")]),
+            TableRow::new(vec![
+                cell("a|b"),
+                cell("c").add_paragraph(Paragraph::new().add_run(Run::new().add_text("d"))),
+            ]),
+        ]));
+
+    assert_eq!(
+        docx_to_markdown(&docx),
+        "标题\n\n| 名称 | 说明 |\n| --- | --- |\n| a\\|b | c<br>d |\n"
+    );
+}
+
+#[test]
+fn render_to_text_keeps_entity_like_literal() {
+    use docx_rs::Run;
+
+    let mut template = std::io::Cursor::new(vec![]);
+    Docx::new()
+        .add_paragraph(Paragraph::new().add_run(Run::new().add_text("{{name}}")))
+        .build()
+        .pack(&mut template)
+        .unwrap();
+    let rendered = docx_handlebars::render_handlebars(
+        template.into_inner(),
+        &serde_json::json!({"name": "AT&amp;T &lt;b&gt; & <i>"}),
+    )
+    .unwrap();
+
+    // 文本中的实体样式字面量原样输出
+    assert_eq!(
+        convert_docx(&rendered, "txt").unwrap().as_deref(),
+        Some("AT&amp;T &lt;b&gt; & <i>")
+    );
+    assert!(
+        convert_docx(&rendered, "HTML")
+            .unwrap()
+            .unwrap()
+            .contains("<p>AT&amp;amp;T &amp;lt;b&amp;gt; &amp; &lt;i&gt;</p>")
+    );
+    assert_eq!(convert_docx(&rendered, "docx").unwrap(), None);
+}
@@ -0,0 +1,117 @@
+use std::{collections::HashMap, io::Read};
+
+use docx_rs::Docx;
+
+use super::read::read_docx_checked;
+
+// sql和html暂不支持的元素，json中的type -> 名称
+const UNSUPPORTED_TYPES: [(&str, &str); 8] = [
+    ("structuredDataTag", "内容控件（SDT）"),
+    ("footnoteReference", "脚注"),
+    ("tableOfContents", "目录"),
+    ("drawing", "图片等绘图对象"),
+    ("shape", "文本框等形状"),
+    ("insert", "修订（插入）"),
+    ("delete", "修订（删除）"),
+    ("commentRangeStart", "批注"),
+];
+
+// 报告文档中sql和html暂不支持、修改后可能丢失的内容
+pub fn analyze(docx: &Docx) -> Vec<String> {
+    // 使用json遍历所有元素
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let document = serde_json::to_value(&docx.document).unwrap_or(serde_json::Value::Null);
+    count_types(&document, &mut counts);
+
+    let mut report = vec![];
+    for (type_name, name) in UNSUPPORTED_TYPES {
+        if let Some(count) = counts.get(type_name) {
+            report.push(format!("{name}: {count}处"));
+        }
+    }
+
+    // 页眉页脚
+    let section =
+        serde_json::to_value(&docx.document.section_property).unwrap_or(serde_json::Value::Null);
+    let has_header_footer = [
+        "header",
+        "firstHeader",
+        "evenHeader",
+        "footer",
+        "firstFooter",
+        "evenFooter",
+    ]
+    .iter()
+    .any(|key| section.get(key).is_some_and(|item| !item.is_null()));
+    if has_header_footer {
+        report.push("页眉页脚".to_string());
+    }
+
+    report
+}
+
+// 读取docx并报告不支持的内容，公式在read_docx时会被丢弃，需要从document.xml中查找
+pub fn analyze_bytes(docx_bytes: &[u8]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let docx = read_docx_checked(docx_bytes)?;
+    let mut report = analyze(&docx);
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes))?;
+    let mut document_xml = String::new();
+    archive
+        .by_name("word/document.xml")?
+        .read_to_string(&mut document_xml)?;
+    let equations =
+        document_xml.matches("<m:oMath>").count() + document_xml.matches("<m:oMath ").count();
+    if equations > 0 {
+        report.push(format!("公式: {equations}处"));
+    }
+
+    Ok(report)
+}
+
+fn count_types(value: &serde_json::Value, counts: &mut HashMap<String, usize>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(type_name) = map.get("type").and_then(|item| item.as_str()) {
+                *counts.entry(type_name.to_string()).or_default() += 1;
+            }
+            for item in map.values() {
+                count_types(item, counts);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for item in arr {
+                count_types(item, counts);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use docx_rs::{Insert, Paragraph, Run, StructuredDataTag};
+
+    #[test]
+    fn report_content_control_and_revision() {
+        let docx = Docx::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("正文")))
+            .add_paragraph(
+                Paragraph::new()
+                    .add_structured_data_tag(
+                        StructuredDataTag::new().add_run(Run::new().add_text("a")),
+                    )
+                    .add_insert(Insert::new(Run::new().add_text("b"))),
+            );
+        assert_eq!(
+            analyze(&docx),
+            vec![
+                "内容控件（SDT）: 1处".to_string(),
+                "修订（插入）: 1处".to_string()
+            ]
+        );
+
+        assert!(analyze(&Docx::new()).is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use docx_rs::{Document, DocumentChild, TableChild, TableRowChild};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::sql_parser::cell::cell_text;
+
+// cell在文档中的位置
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CellRef {
+    // 表格在文档中的序号（从0开始，只计算表格）
+    pub table_index: usize,
+
+    // 行号（从0开始）
+    pub row_index: usize,
+
+    // 列号（从0开始，按单元格计算，不展开gridSpan）
+    pub column_index: usize,
+
+    // cell的文本
+    pub text: String,
+}
+
+impl std::fmt::Display for CellRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "表格{} 第{}行 第{}列: {}",
+            self.table_index, self.row_index, self.column_index, self.text
+        )
+    }
+}
+
+// 查找文本满足条件的cell
+pub fn find_cells<F: Fn(&str) -> bool>(docx: &Document, pred: F) -> Vec<CellRef> {
+    let mut cells = vec![];
+    let tables = docx.children.iter().filter_map(|doc_child| {
+        if let DocumentChild::Table(t_box) = doc_child {
+            Some(t_box)
+        } else {
+            None
+        }
+    });
+    for (table_index, t_box) in tables.enumerate() {
+        for (row_index, TableChild::TableRow(table_row)) in t_box.rows.iter().enumerate() {
+            for (column_index, TableRowChild::TableCell(table_cell)) in
+                table_row.cells.iter().enumerate()
+            {
+                let text = cell_text(table_cell);
+                if pred(&text) {
+                    cells.push(CellRef {
+                        table_index,
+                        row_index,
+                        column_index,
+                        text,
+                    });
+                }
+            }
+        }
+    }
+
+    cells
+}
+
+// 查找文本匹配正则的cell
+pub fn find_cells_regex(docx: &Document, pattern: &str) -> Result<Vec<CellRef>, regex::Error> {
+    let regex = Regex::new(pattern)?;
+    Ok(find_cells(docx, |text| regex.is_match(text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_parser::test_util::{merged_table_docx, table_docx};
+
+    #[test]
+    fn find_cells_by_regex() {
+        let docx = table_docx(&[&["姓名", "年龄"], &["张三", "18"], &["李四", "20"]]);
+        let cells = find_cells_regex(&docx.document, r"^\d+$").unwrap();
+        assert_eq!(
+            cells
+                .iter()
+                .map(|item| (item.row_index, item.column_index))
+                .collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1)]
+        );
+        assert_eq!(cells[1].to_string(), "表格0 第2行 第1列: 20");
+
+        assert!(find_cells_regex(&docx.document, "(").is_err());
+    }
+
+    #[test]
+    fn find_cells_in_merged_table() {
+        let docx = merged_table_docx();
+        let cells = find_cells(&docx.document, |text| text == "d");
+        assert_eq!(cells.len(), 1);
+        assert_eq!((cells[0].row_index, cells[0].column_index), (1, 2));
+    }
+}
@@ -0,0 +1,64 @@
+use docx_rs::Docx;
+use serde_json::{Value, json};
+
+use crate::sql_parser::cell::json_hash;
+
+// 文档内容的指纹：正文（含页眉页脚）、样式、编号、批注和脚注的sha256
+// 不包含docProps中的创建、修改时间等元数据，段落id每次读取都会重新生成，也不参与计算
+pub fn document_fingerprint(docx: &Docx) -> String {
+    let mut content = json!({
+        "document": docx.document,
+        "styles": docx.styles,
+        "numberings": docx.numberings,
+        "comments": docx.comments,
+        "footnotes": docx.footnotes,
+    });
+    remove_paragraph_ids(&mut content);
+    json_hash(&content)
+}
+
+// 段落序列化后包含id和hasNumbering字段，移除其中的id
+fn remove_paragraph_ids(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if map.contains_key("hasNumbering") {
+                map.remove("id");
+            }
+            map.values_mut().for_each(remove_paragraph_ids);
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(remove_paragraph_ids),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use docx_rs::{Paragraph, Run, read_docx};
+
+    fn pack(docx: Docx) -> Vec<u8> {
+        let mut buf = std::io::Cursor::new(vec![]);
+        docx.build().pack(&mut buf).unwrap();
+        buf.into_inner()
+    }
+
+    #[test]
+    fn fingerprint_ignores_metadata() {
+        let docx =
+            || Docx::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("正文")));
+        let first = pack(docx().created_at("2024-01-01T00:00:00Z"));
+        let second = pack(docx().created_at("2025-06-01T08:00:00Z"));
+        assert_eq!(
+            document_fingerprint(&read_docx(&first).unwrap()),
+            document_fingerprint(&read_docx(&second).unwrap())
+        );
+
+        let changed = pack(
+            Docx::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("修改后"))),
+        );
+        assert_ne!(
+            document_fingerprint(&read_docx(&first).unwrap()),
+            document_fingerprint(&read_docx(&changed).unwrap())
+        );
+    }
+}
@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+
+use base64::{Engine, engine::general_purpose};
+use image::ImageFormat;
+use serde_json::Value;
+
+// Word无法显示webp、avif，webp转为png后再嵌入，其它格式原样返回
+pub fn to_word_image(path: &str, content: Vec<u8>) -> Vec<u8> {
+    match image::guess_format(&content) {
+        Ok(ImageFormat::WebP) => {
+            let png = image::load_from_memory_with_format(&content, ImageFormat::WebP)
+                .map_err(|e| e.to_string())
+                .and_then(|img| {
+                    let mut png = Cursor::new(vec![]);
+                    img.write_to(&mut png, ImageFormat::Png)
+                        .map_err(|e| e.to_string())?;
+                    Ok(png.into_inner())
+                });
+            match png {
+                Ok(png) => {
+                    println!("警告: {path} 为webp格式，已转为png");
+                    png
+                }
+                Err(e) => {
+                    println!("警告: {path} 为webp格式，转为png失败, {e}");
+                    content
+                }
+            }
+        }
+        Ok(ImageFormat::Avif) => {
+            println!("警告: {path} 为avif格式，Word无法显示，暂不支持转换");
+            content
+        }
+        _ => content,
+    }
+}
+
+// 解析data uri，如data:image/png;base64,iVBOR...，返回媒体类型和解码后的内容
+// 不是data uri时返回None；只支持base64编码
+pub fn parse_data_uri(value: &str) -> Option<Result<(String, Vec<u8>), String>> {
+    let rest = value.strip_prefix("data:")?;
+    let Some((meta, data)) = rest.split_once(',') else {
+        return Some(Err("data uri缺少逗号".to_string()));
+    };
+    let Some(media_type) = meta.strip_suffix(";base64") else {
+        return Some(Err("data uri只支持base64编码".to_string()));
+    };
+    // 换行的base64先去掉空白
+    let data = data.split_whitespace().collect::<String>();
+    Some(
+        general_purpose::STANDARD
+            .decode(data)
+            .map(|content| (media_type.to_string(), content))
+            .map_err(|e| format!("data uri的base64无法解码, {e}")),
+    )
+}
+
+// 把json中".image"结尾字段的图片路径替换为base64，并添加去掉后缀的同名字段
+// read_files按顺序读取本地或网络图片；data uri直接解码，同时添加"{name}.image_type"字段
+// 返回替换为占位图的emf、wmf，渲染后需要换回
+pub fn images_to_base64(
+    value: &mut Value,
+    read_files: impl FnOnce(&[String]) -> Vec<Result<Vec<u8>, String>>,
+) -> VectorImages {
+    // 先收集所有图片路径（相同路径只保留一次），再一起读取
+    let mut paths: Vec<String> = vec![];
+    collect_image_paths(value, &mut paths);
+    // data uri直接解码，不作为文件读取
+    let file_paths = paths
+        .iter()
+        .filter(|path| !path.starts_with("data:"))
+        .cloned()
+        .collect::<Vec<String>>();
+    let mut contents = read_files(&file_paths).into_iter();
+    // 每个路径只转码、编码一次，data uri同时记录媒体类型
+    let mut vectors = VectorImages::default();
+    let images: HashMap<String, (String, Option<String>)> = paths
+        .into_iter()
+        .map(|path| {
+            let (name, content, media_type) = match parse_data_uri(&path) {
+                Some(Ok((media_type, content))) => ("data uri", content, Some(media_type)),
+                Some(Err(e)) => {
+                    println!("警告: 图片{e}");
+                    ("data uri", vec![], None)
+                }
+                None => (
+                    path.as_str(),
+                    contents.next().and_then(|item| item.ok()).unwrap_or(vec![]),
+                    None,
+                ),
+            };
+            let content = to_word_image(name, content);
+            let content = vectors.replace(content);
+            let base64 = general_purpose::STANDARD.encode(&content);
+            (path, (base64, media_type))
+        })
+        .collect();
+
+    fill_image_base64(value, &images);
+    vectors
+}
+
+// 收集json中所有".image"结尾的字段值，按出现顺序去重
+fn collect_image_paths(value: &Value, paths: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter() {
+                if k.ends_with(".image")
+                    && let Value::String(map_value) = v
+                    && !paths.contains(map_value)
+                {
+                    paths.push(map_value.clone());
+                }
+                collect_image_paths(v, paths);
+            }
+        }
+        Value::Array(arr) => {
+            for ele in arr {
+                collect_image_paths(ele, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn fill_image_base64(value: &mut Value, images: &HashMap<String, (String, Option<String>)>) {
+    match value {
+        Value::Object(map) => {
+            let mut add_items: HashMap<String, Value> = HashMap::new();
+            for (k, v) in map.iter_mut() {
+                if k.ends_with(".image")
+                    && let Value::String(map_value) = v
+                {
+                    let (content, media_type) =
+                        images.get(map_value.as_str()).cloned().unwrap_or_default();
+                    let name = k.strip_suffix(".image").unwrap_or(k);
+                    add_items.insert(name.to_string(), Value::String(content.clone()));
+                    // data uri的媒体类型，如image/png
+                    if let Some(media_type) = media_type {
+                        add_items.insert(format!("{name}.image_type"), Value::String(media_type));
+                    }
+                    *v = Value::String(content);
+                }
+                fill_image_base64(v, images);
+            }
+            // 添加
+            map.extend(add_items);
+        }
+        Value::Array(arr) => {
+            for ele in arr {
+                fill_image_base64(ele, images);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Office常用的矢量图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorFormat {
+    Emf,
+    Wmf,
+}
+
+impl VectorFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VectorFormat::Emf => "emf",
+            VectorFormat::Wmf => "wmf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            VectorFormat::Emf => "image/x-emf",
+            VectorFormat::Wmf => "image/x-wmf",
+        }
+    }
+}
+
+// 按文件头识别emf、wmf
+pub fn detect_vector_format(content: &[u8]) -> Option<VectorFormat> {
+    // emf: EMR_HEADER的类型为1，偏移40处为" EMF"签名
+    if content.len() >= 44 && read_u32(content, 0) == 1 && &content[40..44] == b" EMF" {
+        return Some(VectorFormat::Emf);
+    }
+    // wmf: 可放置wmf以D7CDC69A开头；标准wmf类型为1或2，头长度为9
+    if content.starts_with(&[0xD7, 0xCD, 0xC6, 0x9A]) {
+        return Some(VectorFormat::Wmf);
+    }
+    if content.len() >= 18 && matches!(read_u16(content, 0), 1 | 2) && read_u16(content, 2) == 9 {
+        return Some(VectorFormat::Wmf);
+    }
+    None
+}
+
+// 矢量图片按96dpi换算的像素尺寸，无法读取时按96x96处理
+fn vector_size(format: VectorFormat, content: &[u8]) -> (u32, u32) {
+    let size = match format {
+        // rclFrame，单位0.01毫米
+        VectorFormat::Emf => {
+            let width = read_u32(content, 32) as i32 - read_u32(content, 24) as i32;
+            let height = read_u32(content, 36) as i32 - read_u32(content, 28) as i32;
+            (width as f64 * 96.0 / 2540.0, height as f64 * 96.0 / 2540.0)
+        }
+        // 可放置wmf的BoundingBox和每英寸单位数
+        VectorFormat::Wmf if content.len() >= 16 && content.starts_with(&[0xD7, 0xCD]) => {
+            let width = read_u16(content, 10) as i16 as f64 - read_u16(content, 6) as i16 as f64;
+            let height = read_u16(content, 12) as i16 as f64 - read_u16(content, 8) as i16 as f64;
+            let inch = read_u16(content, 14).max(1) as f64;
+            (width * 96.0 / inch, height * 96.0 / inch)
+        }
+        VectorFormat::Wmf => (96.0, 96.0),
+    };
+    match size {
+        (width, height) if width >= 1.0 && height >= 1.0 => {
+            (width.round() as u32, height.round() as u32)
+        }
+        _ => (96, 96),
+    }
+}
+
+fn read_u16(content: &[u8], offset: usize) -> u16 {
+    content
+        .get(offset..offset + 2)
+        .map(|item| u16::from_le_bytes([item[0], item[1]]))
+        .unwrap_or(0)
+}
+
+fn read_u32(content: &[u8], offset: usize) -> u32 {
+    content
+        .get(offset..offset + 4)
+        .map(|item| u32::from_le_bytes([item[0], item[1], item[2], item[3]]))
+        .unwrap_or(0)
+}
+
+// 模板的img helper只能读取位图尺寸，且固定保存为png
+// 渲染前把矢量图片替换为带尺寸的占位png，渲染后再换回原始内容并修正扩展名和类型
+#[derive(Debug, Default)]
+pub struct VectorImages {
+    images: Vec<(VectorFormat, Vec<u8>)>,
+}
+
+impl VectorImages {
+    // emf、wmf返回占位png，其它格式原样返回
+    pub fn replace(&mut self, content: Vec<u8>) -> Vec<u8> {
+        let Some(format) = detect_vector_format(&content) else {
+            return content;
+        };
+        let placeholder = placeholder_png(self.images.len(), vector_size(format, &content));
+        self.images.push((format, content));
+        placeholder
+    }
+
+    // 把渲染结果中的占位png换回矢量图片
+    pub fn restore(&self, docx_bytes: Vec<u8>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if self.images.is_empty() {
+            return Ok(docx_bytes);
+        }
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(&docx_bytes))?;
+        let mut files: Vec<(String, Vec<u8>)> = vec![];
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index)?;
+            let mut content = vec![];
+            file.read_to_end(&mut content)?;
+            files.push((file.name().to_string(), content));
+        }
+
+        // 占位png改名为原格式，记录需要修改的关系
+        let mut renamed: Vec<(String, String)> = vec![];
+        let mut formats: Vec<VectorFormat> = vec![];
+        for (name, content) in files.iter_mut() {
+            let Some(media) = name.strip_prefix("word/").map(|item| item.to_string()) else {
+                continue;
+            };
+            let Some(&(format, ref original)) =
+                placeholder_index(content).and_then(|index| self.images.get(index))
+            else {
+                continue;
+            };
+            let Some(stem) = media.strip_suffix(".png") else {
+                continue;
+            };
+            let target = format!("{stem}.{}", format.extension());
+            *name = format!("word/{target}");
+            *content = original.clone();
+            renamed.push((media, target));
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+
+        for (name, content) in files.iter_mut() {
+            if name == "word/_rels/document.xml.rels" {
+                let mut rels = String::from_utf8(std::mem::take(content))?;
+                for (from, to) in &renamed {
+                    rels = rels.replace(&format!("Target=\"{from}\""), &format!("Target=\"{to}\""));
+                }
+                *content = rels.into_bytes();
+            } else if name == "[Content_Types].xml" {
+                let mut types = String::from_utf8(std::mem::take(content))?;
+                for format in &formats {
+                    if !types.contains(&format!("Extension=\"{}\"", format.extension())) {
+                        types = types.replace(
+                            "</Types>",
+                            &format!(
+                                "<Default Extension=\"{}\" ContentType=\"{}\" /></Types>",
+                                format.extension(),
+                                format.content_type()
+                            ),
+                        );
+                    }
+                }
+                *content = types.into_bytes();
+            }
+        }
+
+        let mut buf = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buf);
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, content) in files {
+            writer.start_file(name, options)?;
+            writer.write_all(&content)?;
+        }
+        writer.finish()?;
+        drop(writer);
+
+        Ok(buf.into_inner())
+    }
+}
+
+// 占位png：文件头和IHDR中的宽高足够helper计算尺寸，末尾的序号用于区分不同图片
+const PLACEHOLDER_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR";
+
+fn placeholder_png(index: usize, (width, height): (u32, u32)) -> Vec<u8> {
+    let mut png = PLACEHOLDER_HEADER.to_vec();
+    png.extend_from_slice(&width.to_be_bytes());
+    png.extend_from_slice(&height.to_be_bytes());
+    png.extend_from_slice(&(index as u64).to_be_bytes());
+    png
+}
+
+// 真实png在IHDR后还有数据和校验，长度不会与占位png相同
+fn placeholder_index(content: &[u8]) -> Option<usize> {
+    if content.len() != PLACEHOLDER_HEADER.len() + 16 || !content.starts_with(PLACEHOLDER_HEADER) {
+        return None;
+    }
+    let index: [u8; 8] = content[content.len() - 8..].try_into().ok()?;
+    Some(u64::from_be_bytes(index) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcode_webp_to_png() {
+        // 1x1的无损webp
+        let webp = general_purpose::STANDARD
+            .decode("UklGRhoAAABXRUJQVlA4TA0AAAAvAAAAEAcQERGIiP4HAA==")
+            .unwrap();
+        let png = to_word_image("a.webp", webp);
+        assert_eq!(image::guess_format(&png).unwrap(), ImageFormat::Png);
+
+        // 其它格式不变
+        assert_eq!(to_word_image("a.png", png.clone()), png);
+        assert_eq!(to_word_image("a.txt", b"abc".to_vec()), b"abc".to_vec());
+    }
+
+    #[test]
+    fn decode_data_uri_image_field() {
+        let png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let base64 = general_purpose::STANDARD.encode(&png);
+        let mut value = serde_json::json!({
+            "items": [{"logo.image": format!("data:image/png;base64,{base64}")}]
+        });
+        // data uri不作为文件读取
+        images_to_base64(&mut value, |paths| {
+            assert!(paths.is_empty());
+            vec![]
+        });
+        let item = &value["items"][0];
+        assert_eq!(item["logo"], base64);
+        assert_eq!(item["logo.image"], base64);
+        assert_eq!(item["logo.image_type"], "image/png");
+
+        // 普通路径读取文件，不添加image_type
+        let mut value = serde_json::json!({"logo.image": "images/logo.png"});
+        images_to_base64(&mut value, |paths| {
+            assert_eq!(paths, ["images/logo.png"]);
+            vec![Ok(png.clone())]
+        });
+        assert_eq!(value["logo"], base64);
+        assert!(value.get("logo.image_type").is_none());
+
+        assert_eq!(parse_data_uri("images/logo.png"), None);
+        assert_eq!(
+            parse_data_uri("data:image/svg+xml,<svg/>"),
+            Some(Err("data uri只支持base64编码".to_string()))
+        );
+        assert!(matches!(
+            parse_data_uri("data:image/png;base64,%%%"),
+            Some(Err(_))
+        ));
+    }
+
+    fn emf(width: i32, height: i32) -> Vec<u8> {
+        let mut emf = vec![0u8; 88];
+        emf[0..4].copy_from_slice(&1u32.to_le_bytes());
+        emf[32..36].copy_from_slice(&width.to_le_bytes());
+        emf[36..40].copy_from_slice(&height.to_le_bytes());
+        emf[40..44].copy_from_slice(b" EMF");
+        emf
+    }
+
+    #[test]
+    fn detect_vector_magic() {
+        assert_eq!(
+            detect_vector_format(&emf(2540, 2540)),
+            Some(VectorFormat::Emf)
+        );
+        assert_eq!(vector_size(VectorFormat::Emf, &emf(2540, 5080)), (96, 192));
+
+        // 可放置wmf，1440单位每英寸
+        let mut wmf = vec![0xD7, 0xCD, 0xC6, 0x9A, 0, 0, 0, 0, 0, 0];
+        wmf.extend_from_slice(&2880u16.to_le_bytes());
+        wmf.extend_from_slice(&1440u16.to_le_bytes());
+        wmf.extend_from_slice(&1440u16.to_le_bytes());
+        assert_eq!(detect_vector_format(&wmf), Some(VectorFormat::Wmf));
+        assert_eq!(vector_size(VectorFormat::Wmf, &wmf), (192, 96));
+
+        let mut standard = vec![1, 0, 9, 0];
+        standard.resize(18, 0);
+        assert_eq!(detect_vector_format(&standard), Some(VectorFormat::Wmf));
+
+        assert_eq!(detect_vector_format(b"\x89PNG\r\n\x1a\n"), None);
+        assert_eq!(detect_vector_format(b"abc"), None);
+    }
+
+    #[test]
+    fn restore_vector_placeholder() {
+        let original = emf(2540, 2540);
+        let mut vectors = VectorImages::default();
+        assert_eq!(vectors.replace(b"abc".to_vec()), b"abc".to_vec());
+        let placeholder = vectors.replace(original.clone());
+        assert_eq!(placeholder_index(&placeholder), Some(0));
+        assert_eq!(placeholder[16..24], [0, 0, 0, 96, 0, 0, 0, 96]);
+
+        // 模拟img helper的输出
+        let mut buf = Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buf);
+        let options = zip::write::FileOptions::default();
+        let files: [(&str, &[u8]); 3] = [
+            (
+                "[Content_Types].xml",
+                br#"<Types><Default Extension="png" ContentType="image/png" /></Types>"#,
+            ),
+            (
+                "word/_rels/document.xml.rels",
+                br#"<Relationships><Relationship Id="rId9" Target="media/rId9.png"></Relationship></Relationships>"#,
+            ),
+            ("word/media/rId9.png", &placeholder),
+        ];
+        for (name, content) in files {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+        drop(writer);
+
+        let docx = vectors.restore(buf.into_inner()).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(docx)).unwrap();
+        let mut content = vec![];
+        archive
+            .by_name("word/media/rId9.emf")
+            .unwrap()
+            .read_to_end(&mut content)
+            .unwrap();
+        assert_eq!(content, original);
+        assert!(archive.by_name("word/media/rId9.png").is_err());
+
+        let mut rels = String::new();
+        archive
+            .by_name("word/_rels/document.xml.rels")
+            .unwrap()
+            .read_to_string(&mut rels)
+            .unwrap();
+        assert!(rels.contains(r#"Target="media/rId9.emf""#));
+        let mut types = String::new();
+        archive
+            .by_name("[Content_Types].xml")
+            .unwrap()
+            .read_to_string(&mut types)
+            .unwrap();
+        assert!(types.contains(r#"<Default Extension="emf" ContentType="image/x-emf" />"#));
+    }
+}
@@ -0,0 +1,196 @@
+use docx_rs::{Document, DocumentChild, Paragraph, Run, TableChild, TableRowChild};
+
+use crate::sql_parser::cell::{json_hash, set_cell_text};
+
+// 段落相对表格的插入位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InsertPosition {
+    Before,
+    After,
+}
+
+// 在哈希为table_hash的表格前面或后面插入一个段落，返回插入的段落数
+// 表格的哈希与tables表的hash列一致，相同的表格都会插入
+pub fn insert_paragraph_near_table(
+    docx: &mut Document,
+    table_hash: &str,
+    text: &str,
+    position: InsertPosition,
+) -> usize {
+    // 先找出所有位置，再从后往前插入，避免插入后下标错位
+    let indexes = docx
+        .children
+        .iter()
+        .enumerate()
+        .filter_map(|(index, doc_child)| match doc_child {
+            DocumentChild::Table(t_box) if json_hash(t_box) == table_hash => Some(index),
+            _ => None,
+        })
+        .collect::<Vec<usize>>();
+
+    for index in indexes.iter().rev() {
+        let paragraph = Paragraph::new().add_run(Run::new().add_text(text));
+        let insert_index = match position {
+            InsertPosition::Before => *index,
+            InsertPosition::After => index + 1,
+        };
+        docx.children
+            .insert(insert_index, DocumentChild::Paragraph(Box::new(paragraph)));
+    }
+
+    indexes.len()
+}
+
+// 把哈希为table_hash的表格的第row_index行（从0开始）复制times份，插入到该行之后，返回插入的行数
+// 复制的行保留行和cell的格式，clear为true时清空cell的文本，保留第一个段落和run的属性
+pub fn duplicate_row(
+    docx: &mut Document,
+    table_hash: &str,
+    row_index: usize,
+    times: usize,
+    clear: bool,
+) -> usize {
+    let mut inserted = 0;
+    for doc_child in docx.children.iter_mut() {
+        let DocumentChild::Table(t_box) = doc_child else {
+            continue;
+        };
+        if json_hash(t_box) != table_hash {
+            continue;
+        }
+        let Some(TableChild::TableRow(template)) = t_box.rows.get(row_index) else {
+            continue;
+        };
+        let mut template = template.clone();
+        if clear {
+            for TableRowChild::TableCell(table_cell) in template.cells.iter_mut() {
+                set_cell_text(table_cell, "");
+            }
+        }
+        for offset in 1..=times {
+            t_box
+                .rows
+                .insert(row_index + offset, TableChild::TableRow(template.clone()));
+        }
+        inserted += times;
+    }
+
+    inserted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx_util::read::{pack_to_vec, read_docx_checked};
+    use crate::sql_parser::cell::paragraph_children_text;
+    use crate::sql_parser::test_util::{embedded_images, image_docx, scan, table_docx, text_cell};
+    use docx_rs::{Table, TableRow};
+    use gluesql::core::data::Value;
+
+    // 正文结构，段落为文本，表格为"<table>"
+    fn outline(docx: &Document) -> Vec<String> {
+        docx.children
+            .iter()
+            .map(|doc_child| match doc_child {
+                DocumentChild::Paragraph(paragraph) => paragraph_children_text(&paragraph.children),
+                DocumentChild::Table(_) => "<table>".to_string(),
+                _ => "".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn insert_before_and_after_matched_tables() {
+        let mut docx = table_docx(&[&["a"]])
+            .add_table(Table::new(vec![]))
+            .add_table(Table::new(vec![]));
+        let hash = match &scan(&mut docx, "tables")[1]["hash"] {
+            Value::Str(hash) => hash.clone(),
+            _ => unreachable!(),
+        };
+
+        // 两个空表格的哈希相同，都会插入
+        assert_eq!(
+            insert_paragraph_near_table(&mut docx.document, &hash, "说明", InsertPosition::Before),
+            2
+        );
+        assert_eq!(
+            insert_paragraph_near_table(&mut docx.document, &hash, "小结", InsertPosition::After),
+            2
+        );
+        assert_eq!(
+            outline(&docx.document),
+            vec![
+                "<table>", "说明", "<table>", "小结", "说明", "<table>", "小结"
+            ]
+        );
+
+        assert_eq!(
+            insert_paragraph_near_table(&mut docx.document, "none", "x", InsertPosition::After),
+            0
+        );
+    }
+
+    #[test]
+    fn duplicate_template_row() {
+        let mut docx = table_docx(&[&["姓名", "年龄"], &["张三", "18"], &["合计", "1"]]);
+        let hash = match &scan(&mut docx, "tables")[0]["hash"] {
+            Value::Str(hash) => hash.clone(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(duplicate_row(&mut docx.document, &hash, 1, 2, true), 2);
+        let contents = scan(&mut docx, "cell")
+            .iter()
+            .map(|row| row["content"].clone())
+            .collect::<Vec<_>>();
+        let text = |item: &str| Value::Str(item.to_string());
+        assert_eq!(
+            contents,
+            vec![
+                text("姓名"),
+                text("年龄"),
+                text("张三"),
+                text("18"),
+                text(""),
+                text(""),
+                text(""),
+                text(""),
+                text("合计"),
+                text("1"),
+            ]
+        );
+
+        // 不清空时原样复制；行号超出范围时不插入
+        let hash = match &scan(&mut docx, "tables")[0]["hash"] {
+            Value::Str(hash) => hash.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(duplicate_row(&mut docx.document, &hash, 0, 1, false), 1);
+        assert_eq!(scan(&mut docx, "cell")[2]["content"], text("姓名"));
+        assert_eq!(duplicate_row(&mut docx.document, "none", 0, 1, false), 0);
+        let hash = match &scan(&mut docx, "tables")[0]["hash"] {
+            Value::Str(hash) => hash.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(duplicate_row(&mut docx.document, &hash, 10, 1, false), 0);
+    }
+
+    #[test]
+    fn duplicate_row_keeps_images() {
+        let docx = image_docx(&[("rId4", b"logo"), ("rId5", b"figure")])
+            .add_table(Table::new(vec![TableRow::new(vec![text_cell("a")])]));
+        let mut docx = read_docx_checked(&pack_to_vec(docx).unwrap()).unwrap();
+        let hash = match &scan(&mut docx, "tables")[0]["hash"] {
+            Value::Str(hash) => hash.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(duplicate_row(&mut docx.document, &hash, 0, 1, false), 1);
+
+        let docx_bytes = pack_to_vec(docx).unwrap();
+        assert_eq!(
+            embedded_images(&docx_bytes),
+            vec![b"logo".to_vec(), b"figure".to_vec()]
+        );
+    }
+}
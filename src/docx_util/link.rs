@@ -0,0 +1,150 @@
+use docx_rs::{
+    DocumentChild, Docx, HyperlinkData, Paragraph, ParagraphChild, Table, TableCellContent,
+    TableChild, TableRowChild,
+};
+use serde::Serialize;
+
+use crate::sql_parser::cell::paragraph_children_text;
+
+// 文档中的一个超链接
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct HyperlinkInfo {
+    // 链接所在位置，如"段落3"、"表格0 第1行 第2列"
+    pub location: String,
+
+    // 显示的文本
+    pub text: String,
+
+    // 外部链接的地址，文档内锚点以"#"开头
+    pub target: String,
+}
+
+impl HyperlinkInfo {
+    // 是否为可访问的网络地址
+    pub fn is_external(&self) -> bool {
+        self.target.starts_with("http://") || self.target.starts_with("https://")
+    }
+}
+
+impl std::fmt::Display for HyperlinkInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.location, self.text, self.target)
+    }
+}
+
+// 按顺序列出正文段落和表格（包括嵌套表格）中的超链接
+pub fn list_hyperlinks(docx: &Docx) -> Vec<HyperlinkInfo> {
+    let mut links = vec![];
+    let mut paragraph_index = 0;
+    let mut table_index = 0;
+    for doc_child in &docx.document.children {
+        match doc_child {
+            DocumentChild::Paragraph(paragraph) => {
+                paragraph_links(
+                    docx,
+                    paragraph,
+                    &format!("段落{paragraph_index}"),
+                    &mut links,
+                );
+                paragraph_index += 1;
+            }
+            DocumentChild::Table(table) => {
+                table_links(docx, table, &format!("表格{table_index}"), &mut links);
+                table_index += 1;
+            }
+            _ => {}
+        }
+    }
+
+    links
+}
+
+fn table_links(docx: &Docx, table: &Table, location: &str, links: &mut Vec<HyperlinkInfo>) {
+    for (row_index, TableChild::TableRow(table_row)) in table.rows.iter().enumerate() {
+        for (column_index, TableRowChild::TableCell(table_cell)) in
+            table_row.cells.iter().enumerate()
+        {
+            let location = format!("{location} 第{row_index}行 第{column_index}列");
+            for content in &table_cell.children {
+                match content {
+                    TableCellContent::Paragraph(paragraph) => {
+                        paragraph_links(docx, paragraph, &location, links)
+                    }
+                    TableCellContent::Table(table) => {
+                        table_links(docx, table, &format!("{location} 嵌套表格"), links)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn paragraph_links(
+    docx: &Docx,
+    paragraph: &Paragraph,
+    location: &str,
+    links: &mut Vec<HyperlinkInfo>,
+) {
+    for item in &paragraph.children {
+        if let ParagraphChild::Hyperlink(hyperlink) = item {
+            links.push(HyperlinkInfo {
+                location: location.to_string(),
+                text: paragraph_children_text(&hyperlink.children),
+                target: hyperlink_target(docx, &hyperlink.link),
+            });
+        }
+    }
+}
+
+// 从文件读取的外部链接只有rid，地址在document.xml.rels中
+fn hyperlink_target(docx: &Docx, link: &HyperlinkData) -> String {
+    match link {
+        HyperlinkData::External { rid, path } if path.is_empty() => docx
+            .hyperlinks
+            .iter()
+            .find(|(id, _, _)| id == rid)
+            .map(|(_, path, _)| path.clone())
+            .unwrap_or(rid.clone()),
+        HyperlinkData::External { path, .. } => path.clone(),
+        HyperlinkData::Anchor { anchor } => format!("#{anchor}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use docx_rs::{Hyperlink, HyperlinkType, Run, TableCell, TableRow};
+
+    #[test]
+    fn list_links_in_paragraphs_and_cells() {
+        let link = |url: &str, text: &str, link_type: HyperlinkType| {
+            Paragraph::new()
+                .add_hyperlink(Hyperlink::new(url, link_type).add_run(Run::new().add_text(text)))
+        };
+        let docx = Docx::new()
+            .add_paragraph(link("https://example.com", "官网", HyperlinkType::External))
+            .add_table(Table::new(vec![TableRow::new(vec![
+                TableCell::new(),
+                TableCell::new().add_paragraph(link("目录", "见目录", HyperlinkType::Anchor)),
+            ])]));
+        let mut bytes = std::io::Cursor::new(vec![]);
+        docx.build().pack(&mut bytes).unwrap();
+
+        // 读取后外部链接通过rid查找地址
+        let docx = docx_rs::read_docx(bytes.get_ref()).unwrap();
+        let links = list_hyperlinks(&docx);
+        assert_eq!(
+            links
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "段落0: 官网 -> https://example.com",
+                "表格0 第0行 第1列: 见目录 -> #目录",
+            ]
+        );
+        assert!(links[0].is_external());
+        assert!(!links[1].is_external());
+    }
+}
@@ -0,0 +1,371 @@
+use docx_rs::{
+    BasedOn, DocumentChild, Docx, Link, Next, Paragraph, ParagraphChild, ParagraphProperty, Run,
+    Table, TableCellContent, TableChild, TableRowChild,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use super::read::for_each_pic;
+
+// 把other的正文追加到base后面
+// 样式按id合并，base中已有的样式保留base的定义；other的编号整体偏移到base已有编号之后，避免列表混在一起
+// 图片的关系id与base重复时重新编号；批注和脚注不会合并
+pub fn append_document(base: &mut Docx, other: &Docx) {
+    // 编号偏移量：numId从1开始，0表示无编号；abstractNumId可以从0开始
+    let num_offset = base
+        .numberings
+        .numberings
+        .iter()
+        .map(|item| item.id)
+        .max()
+        .unwrap_or(0);
+    let abstract_offset = base
+        .numberings
+        .abstract_nums
+        .iter()
+        .map(|item| item.id + 1)
+        .max()
+        .unwrap_or(0);
+
+    for abstract_num in &other.numberings.abstract_nums {
+        let mut abstract_num = abstract_num.clone();
+        abstract_num.id += abstract_offset;
+        base.numberings.abstract_nums.push(abstract_num);
+    }
+    for numbering in &other.numberings.numberings {
+        let mut numbering = numbering.clone();
+        numbering.id += num_offset;
+        numbering.abstract_num_id += abstract_offset;
+        base.numberings.numberings.push(numbering);
+    }
+
+    // 跳过id重复的样式
+    for style in &other.styles.styles {
+        if base
+            .styles
+            .styles
+            .iter()
+            .any(|item| item.style_id == style.style_id)
+        {
+            continue;
+        }
+        let mut style = style.clone();
+        remap_numbering(&mut style.paragraph_property, num_offset);
+        base.styles.styles.push(style);
+    }
+
+    let mut children = other.document.children.clone();
+    for doc_child in children.iter_mut() {
+        match doc_child {
+            DocumentChild::Paragraph(paragraph) => remap_paragraph(paragraph, num_offset),
+            DocumentChild::Table(table) => remap_table(table, num_offset),
+            _ => {}
+        }
+    }
+    remap_pic_ids(&mut base.document.children, &mut children);
+    base.document.children.extend(children);
+}
+
+// 图片按关系id保存为media/{id}.png，id与base中的图片相同时会互相覆盖，重复的id加上序号
+// 同一文档中相同id的图片仍使用同一个新id
+fn remap_pic_ids(base: &mut [DocumentChild], children: &mut [DocumentChild]) {
+    let mut used: HashSet<String> = HashSet::new();
+    for_each_pic(base, &mut |pic| {
+        used.insert(pic.id.clone());
+    });
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    for_each_pic(children, &mut |pic| {
+        if let Some(id) = renamed.get(&pic.id) {
+            pic.id = id.clone();
+            return;
+        }
+        if !used.contains(&pic.id) {
+            used.insert(pic.id.clone());
+            renamed.insert(pic.id.clone(), pic.id.clone());
+            return;
+        }
+        let id = (1..)
+            .map(|index| format!("{}_{index}", pic.id))
+            .find(|id| !used.contains(id))
+            .unwrap_or_default();
+        used.insert(id.clone());
+        renamed.insert(pic.id.clone(), id.clone());
+        pic.id = id;
+    });
+}
+
+fn remap_paragraph(paragraph: &mut Paragraph, num_offset: usize) {
+    remap_numbering(&mut paragraph.property, num_offset);
+}
+
+fn remap_table(table: &mut Table, num_offset: usize) {
+    for TableChild::TableRow(table_row) in table.rows.iter_mut() {
+        for TableRowChild::TableCell(table_cell) in table_row.cells.iter_mut() {
+            for content in table_cell.children.iter_mut() {
+                match content {
+                    TableCellContent::Paragraph(paragraph) => {
+                        remap_paragraph(paragraph, num_offset)
+                    }
+                    TableCellContent::Table(table) => remap_table(table, num_offset),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn remap_numbering(property: &mut ParagraphProperty, num_offset: usize) {
+    if let Some(numbering_id) = property
+        .numbering_property
+        .as_mut()
+        .and_then(|item| item.id.as_mut())
+        && numbering_id.id != 0
+    {
+        numbering_id.id += num_offset;
+    }
+}
+
+// 给docx的所有样式id加上前缀，正文中段落、run和表格的样式引用同步修改
+// 用于追加到其他文档之前，避免与base中id相同的样式被跳过而套用base的格式
+// 没有显式样式的段落仍使用合并后文档的默认样式
+pub fn prefix_style_ids(docx: &mut Docx, prefix: &str) {
+    for style in docx.styles.styles.iter_mut() {
+        style.style_id = format!("{prefix}{}", style.style_id);
+        // BasedOn、Next、Link的值是私有的，通过序列化读取
+        if let Some(based_on) = &style.based_on {
+            style.based_on = Some(BasedOn::new(prefixed(based_on, prefix)));
+        }
+        if let Some(next) = &style.next {
+            style.next = Some(Next::new(prefixed(next, prefix)));
+        }
+        if let Some(link) = &style.link {
+            style.link = Some(Link::new(prefixed(link, prefix)));
+        }
+    }
+
+    for doc_child in docx.document.children.iter_mut() {
+        match doc_child {
+            DocumentChild::Paragraph(paragraph) => prefix_paragraph(paragraph, prefix),
+            DocumentChild::Table(table) => prefix_table(table, prefix),
+            _ => {}
+        }
+    }
+}
+
+fn prefixed(value: &impl Serialize, prefix: &str) -> String {
+    let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+    format!("{prefix}{}", value.as_str().unwrap_or(""))
+}
+
+fn prefix_paragraph(paragraph: &mut Paragraph, prefix: &str) {
+    if let Some(style) = paragraph.property.style.as_mut() {
+        style.val = format!("{prefix}{}", style.val);
+    }
+    prefix_runs(&mut paragraph.children, prefix);
+}
+
+fn prefix_runs(children: &mut [ParagraphChild], prefix: &str) {
+    for item in children {
+        match item {
+            ParagraphChild::Run(run) => prefix_run(run, prefix),
+            ParagraphChild::Hyperlink(hyperlink) => prefix_runs(&mut hyperlink.children, prefix),
+            _ => {}
+        }
+    }
+}
+
+fn prefix_run(run: &mut Run, prefix: &str) {
+    if let Some(style) = run.run_property.style.as_mut() {
+        style.val = format!("{prefix}{}", style.val);
+    }
+}
+
+fn prefix_table(table: &mut Table, prefix: &str) {
+    // 表格样式是私有字段，通过序列化读取
+    let property_value = serde_json::to_value(&table.property).unwrap_or(serde_json::Value::Null);
+    if let Some(style) = property_value.get("style").and_then(|item| item.as_str()) {
+        let property = std::mem::take(&mut table.property);
+        table.property = property.style(format!("{prefix}{style}"));
+    }
+    for TableChild::TableRow(table_row) in table.rows.iter_mut() {
+        for TableRowChild::TableCell(table_cell) in table_row.cells.iter_mut() {
+            for content in table_cell.children.iter_mut() {
+                match content {
+                    TableCellContent::Paragraph(paragraph) => prefix_paragraph(paragraph, prefix),
+                    TableCellContent::Table(table) => prefix_table(table, prefix),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx_util::read::{pack_to_vec, read_docx_checked, verify_docx_bytes};
+    use crate::sql_parser::cell::paragraph_children_text;
+    use crate::sql_parser::test_util::{embedded_images, image_docx};
+    use docx_rs::{
+        AbstractNumbering, BuildXML, IndentLevel, Numbering, NumberingId, Style, StyleType,
+        TableCell, TableRow,
+    };
+
+    fn numbered_docx(text: &str) -> Docx {
+        Docx::new()
+            .add_abstract_numbering(AbstractNumbering::new(1))
+            .add_numbering(Numbering::new(1, 1))
+            .add_style(Style::new("Note", StyleType::Paragraph).name(text))
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text(text))
+                    .numbering(NumberingId::new(1), IndentLevel::new(0)),
+            )
+    }
+
+    fn numbering_id(paragraph: &Paragraph) -> usize {
+        paragraph
+            .property
+            .numbering_property
+            .as_ref()
+            .and_then(|item| item.id.as_ref())
+            .map(|item| item.id)
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn append_remaps_numbering_and_skips_duplicate_styles() {
+        let mut base = numbered_docx("第一部分");
+        let other = numbered_docx("第二部分").add_table(Table::new(vec![TableRow::new(vec![
+            TableCell::new().add_paragraph(
+                Paragraph::new().numbering(NumberingId::new(1), IndentLevel::new(0)),
+            ),
+        ])]));
+        let style_count = base.styles.styles.len();
+        append_document(&mut base, &other);
+
+        let children = &base.document.children;
+        assert_eq!(children.len(), 3);
+        let [
+            DocumentChild::Paragraph(first),
+            DocumentChild::Paragraph(second),
+            DocumentChild::Table(table),
+        ] = &children[..]
+        else {
+            panic!("应为两个段落和一个表格");
+        };
+        assert_eq!(numbering_id(first), 1);
+        assert_eq!(numbering_id(second), 2);
+        assert_eq!(paragraph_children_text(&second.children), "第二部分");
+
+        // 表格中的编号同样偏移
+        let TableChild::TableRow(table_row) = &table.rows[0];
+        let TableRowChild::TableCell(table_cell) = &table_row.cells[0];
+        let TableCellContent::Paragraph(cell_paragraph) = &table_cell.children[0] else {
+            panic!("应为段落");
+        };
+        assert_eq!(numbering_id(cell_paragraph), 2);
+
+        let numberings = &base.numberings.numberings;
+        assert_eq!(
+            numberings
+                .iter()
+                .map(|item| (item.id, item.abstract_num_id))
+                .collect::<Vec<_>>(),
+            vec![(1, 1), (2, 3)]
+        );
+
+        // 重复的样式保留base的定义
+        assert_eq!(base.styles.styles.len(), style_count);
+    }
+
+    #[test]
+    fn prefix_styles_before_append() {
+        let mut base = numbered_docx("正文");
+        let mut other = Docx::new()
+            .add_style(
+                Style::new("Note", StyleType::Paragraph)
+                    .based_on("Normal")
+                    .next("Note"),
+            )
+            .add_style(Style::new("Normal", StyleType::Paragraph))
+            .add_style(Style::new("Strong", StyleType::Character))
+            .add_style(Style::new("Grid", StyleType::Table))
+            .add_paragraph(
+                Paragraph::new()
+                    .style("Note")
+                    .add_run(Run::new().add_text("接口").style("Strong")),
+            )
+            .add_table(
+                Table::new(vec![TableRow::new(vec![
+                    TableCell::new().add_paragraph(Paragraph::new().style("Note")),
+                ])])
+                .style("Grid"),
+            );
+        prefix_style_ids(&mut other, "api-");
+        let style_count = base.styles.styles.len();
+        append_document(&mut base, &other);
+
+        // 前缀后不再与base的Note冲突，全部追加
+        assert_eq!(base.styles.styles.len(), style_count + 4);
+        let note = base
+            .styles
+            .styles
+            .iter()
+            .find(|item| item.style_id == "api-Note")
+            .unwrap();
+        assert_eq!(prefixed(note.based_on.as_ref().unwrap(), ""), "api-Normal");
+        assert_eq!(prefixed(note.next.as_ref().unwrap(), ""), "api-Note");
+
+        let xml = String::from_utf8(base.document.build()).unwrap();
+        assert!(xml.contains(r#"<w:pStyle w:val="api-Note" />"#));
+        assert!(xml.contains(r#"<w:rStyle w:val="api-Strong" />"#));
+        assert!(xml.contains(r#"<w:tblStyle w:val="api-Grid" />"#));
+    }
+
+    #[test]
+    fn concat_keeps_images_of_every_document() {
+        let read = |docx: Docx| read_docx_checked(&pack_to_vec(docx).unwrap()).unwrap();
+        let mut base = read(image_docx(&[("rId4", b"base")]));
+        // 与base的关系id重复，同一文档中重复引用的图片保持同一个id
+        let other = read(
+            image_docx(&[("rId4", b"other-4"), ("rId5", b"other-5")])
+                .add_paragraph(Paragraph::new().add_run(Run::new().add_text("正文"))),
+        );
+        let third = read(image_docx(&[("rId4", b"third")]));
+        append_document(&mut base, &other);
+        append_document(&mut base, &third);
+
+        let docx_bytes = pack_to_vec(base).unwrap();
+        assert_eq!(
+            embedded_images(&docx_bytes),
+            vec![
+                b"base".to_vec(),
+                b"other-4".to_vec(),
+                b"other-5".to_vec(),
+                b"third".to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn append_to_base_keeps_base_images() {
+        // 模拟--append-to：base为已有文档，追加的文档先加样式前缀
+        let read = |docx: Docx| read_docx_checked(&pack_to_vec(docx).unwrap()).unwrap();
+        let mut base = read(image_docx(&[("rId4", b"logo"), ("rId5", b"figure")]));
+        let mut other = read(
+            image_docx(&[("rId5", b"api-logo")])
+                .add_style(Style::new("Note", StyleType::Paragraph))
+                .add_paragraph(Paragraph::new().style("Note")),
+        );
+        prefix_style_ids(&mut other, "api-");
+        append_document(&mut base, &other);
+
+        let docx_bytes = pack_to_vec(base).unwrap();
+        assert_eq!(
+            embedded_images(&docx_bytes),
+            vec![b"logo".to_vec(), b"figure".to_vec(), b"api-logo".to_vec()]
+        );
+        assert!(verify_docx_bytes(&docx_bytes).is_ok());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod analyze;
+pub mod find;
+pub mod fingerprint;
+pub mod image;
+pub mod insert;
+pub mod link;
+pub mod merge;
+pub mod read;
+pub mod theme;
+pub mod validate;
@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+
+use docx_rs::{
+    Document, DocumentChild, Docx, DrawingData, InsertChild, ParagraphChild, Pic, RunChild, Table,
+    TableCellContent, TableChild, TableRowChild, read_docx,
+};
+use regex::Regex;
+
+// 旧版.doc（OLE复合文档）的文件头
+const OLE_MAGIC: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+// 检查文件是否为docx：docx是以PK开头的zip压缩包，并且包含word/document.xml
+pub fn check_docx_bytes(docx_bytes: &[u8]) -> Result<(), String> {
+    if docx_bytes.starts_with(&OLE_MAGIC) {
+        return Err(
+            "不是有效的.docx文件（应为ZIP格式），看起来是旧版.doc文件，请在Word中另存为.docx"
+                .to_string(),
+        );
+    }
+    if !docx_bytes.starts_with(b"PK") {
+        return Err("不是有效的.docx文件（应为ZIP格式）".to_string());
+    }
+
+    let has_document = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes))
+        .is_ok_and(|mut archive| archive.by_name("word/document.xml").is_ok());
+    if !has_document {
+        return Err("不是有效的.docx文件（ZIP中缺少word/document.xml）".to_string());
+    }
+
+    Ok(())
+}
+
+// 把docx打包为字节
+pub fn pack_to_vec(docx: Docx) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut buf = std::io::Cursor::new(vec![]);
+    docx.build().pack(&mut buf)?;
+    Ok(buf.into_inner())
+}
+
+// 重新读取生成的docx，确认可以解析，用于保存前发现修改产生的无效xml
+// docx-rs读取某些内容时会panic，也按解析失败处理
+pub fn verify_docx_bytes(docx_bytes: &[u8]) -> Result<(), String> {
+    check_docx_bytes(docx_bytes)?;
+    match std::panic::catch_unwind(|| read_docx(docx_bytes)) {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("读取时出错（panic）".to_string()),
+    }
+}
+
+// 读取docx，文件不是docx时返回明确的错误
+pub fn read_docx_checked(docx_bytes: &[u8]) -> Result<Docx, Box<dyn std::error::Error>> {
+    check_docx_bytes(docx_bytes)?;
+    let mut docx = read_docx(&protect_text_ampersands(docx_bytes)?)?;
+    escape_document_texts(&mut docx.document);
+    fill_pic_images(&mut docx.document, docx_bytes)?;
+    Ok(docx)
+}
+
+// docx-rs读取时不会把图片内容放到Pic中，写入时图片为空，空内容的图片还会合并为同一张
+// 读取后按关系id从docx中取出正文的图片填回，保存时每张图片保持原来的内容
+fn fill_pic_images(
+    document: &mut Document,
+    docx_bytes: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let images = document_images(docx_bytes)?;
+    if images.is_empty() {
+        return Ok(());
+    }
+    for_each_pic(&mut document.children, &mut |pic| {
+        if pic.image.is_empty()
+            && let Some(image) = images.get(&pic.id)
+        {
+            pic.image = image.clone();
+        }
+    });
+    Ok(())
+}
+
+// 正文关系中的图片，关系id到图片内容
+pub(crate) fn document_images(
+    docx_bytes: &[u8],
+) -> Result<HashMap<String, Vec<u8>>, Box<dyn std::error::Error>> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes))?;
+    let mut rels = String::new();
+    match archive.by_name("word/_rels/document.xml.rels") {
+        Ok(mut file) => std::io::Read::read_to_string(&mut file, &mut rels)?,
+        Err(_) => return Ok(HashMap::new()),
+    };
+
+    let relationship = Regex::new(r"<Relationship\s[^>]*>")?;
+    let attribute = |element: &str, name: &str| {
+        Regex::new(&format!(r#"\s{name}="([^"]*)""#))
+            .ok()
+            .and_then(|regex| regex.captures(element))
+            .and_then(|captures| captures.get(1))
+            .map(|item| item.as_str().to_string())
+    };
+    let mut images = HashMap::new();
+    for element in relationship.find_iter(&rels).map(|item| item.as_str()) {
+        let (Some(id), Some(target), Some(rel_type)) = (
+            attribute(element, "Id"),
+            attribute(element, "Target"),
+            attribute(element, "Type"),
+        ) else {
+            continue;
+        };
+        if !rel_type.ends_with("/image") || attribute(element, "TargetMode").is_some() {
+            continue;
+        }
+        // Target相对于word目录，也可能是以/开头的绝对路径
+        let path = match target.strip_prefix('/') {
+            Some(path) => path.to_string(),
+            None => format!("word/{target}"),
+        };
+        if let Ok(mut file) = archive.by_name(&path) {
+            let mut content = vec![];
+            std::io::Read::read_to_end(&mut file, &mut content)?;
+            images.insert(id, content);
+        }
+    }
+    Ok(images)
+}
+
+// 遍历正文段落和表格（包括嵌套表格）中的图片
+pub(crate) fn for_each_pic(children: &mut [DocumentChild], f: &mut impl FnMut(&mut Pic)) {
+    fn run_pics(run_children: &mut [RunChild], f: &mut impl FnMut(&mut Pic)) {
+        for item in run_children.iter_mut() {
+            if let RunChild::Drawing(drawing) = item
+                && let Some(DrawingData::Pic(pic)) = &mut drawing.data
+            {
+                f(pic);
+            }
+        }
+    }
+    fn paragraph_pics(children: &mut [ParagraphChild], f: &mut impl FnMut(&mut Pic)) {
+        for item in children.iter_mut() {
+            match item {
+                ParagraphChild::Run(run) => run_pics(&mut run.children, f),
+                ParagraphChild::Hyperlink(hyperlink) => paragraph_pics(&mut hyperlink.children, f),
+                ParagraphChild::Insert(insert) => {
+                    for insert_child in insert.children.iter_mut() {
+                        if let InsertChild::Run(run) = insert_child {
+                            run_pics(&mut run.children, f);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    fn table_pics(table: &mut Table, f: &mut impl FnMut(&mut Pic)) {
+        for TableChild::TableRow(table_row) in table.rows.iter_mut() {
+            for TableRowChild::TableCell(table_cell) in table_row.cells.iter_mut() {
+                for content in table_cell.children.iter_mut() {
+                    match content {
+                        TableCellContent::Paragraph(paragraph) => {
+                            paragraph_pics(&mut paragraph.children, f)
+                        }
+                        TableCellContent::Table(table) => table_pics(table, f),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    for doc_child in children.iter_mut() {
+        match doc_child {
+            DocumentChild::Paragraph(paragraph) => paragraph_pics(&mut paragraph.children, f),
+            DocumentChild::Table(table) => table_pics(table, f),
+            _ => {}
+        }
+    }
+}
+
+// docx-rs读取时保存的是反转义后的文本，写入时却原样输出，含有&、<的文档保存后xml会损坏
+// 读取后把正文的文本重新转义，与Run::add_text保存的形式一致
+fn escape_document_texts(document: &mut Document) {
+    for doc_child in document.children.iter_mut() {
+        match doc_child {
+            DocumentChild::Paragraph(paragraph) => escape_paragraph_texts(&mut paragraph.children),
+            DocumentChild::Table(table) => escape_table_texts(table),
+            _ => {}
+        }
+    }
+}
+
+fn escape_table_texts(table: &mut Table) {
+    for TableChild::TableRow(table_row) in table.rows.iter_mut() {
+        for TableRowChild::TableCell(table_cell) in table_row.cells.iter_mut() {
+            for content in table_cell.children.iter_mut() {
+                match content {
+                    TableCellContent::Paragraph(paragraph) => {
+                        escape_paragraph_texts(&mut paragraph.children)
+                    }
+                    TableCellContent::Table(table) => escape_table_texts(table),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn escape_paragraph_texts(children: &mut [ParagraphChild]) {
+    let escape_run = |run_children: &mut Vec<RunChild>| {
+        for item in run_children.iter_mut() {
+            if let RunChild::Text(text) = item {
+                text.text = escape_text(&text.text.replace(AMPERSAND_PLACEHOLDER, "&"));
+            }
+        }
+    };
+    for item in children.iter_mut() {
+        match item {
+            ParagraphChild::Run(run) => escape_run(&mut run.children),
+            ParagraphChild::Hyperlink(hyperlink) => escape_paragraph_texts(&mut hyperlink.children),
+            ParagraphChild::Insert(insert) => {
+                for insert_child in insert.children.iter_mut() {
+                    if let InsertChild::Run(run) = insert_child {
+                        escape_run(&mut run.children);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// docx-rs读取w:t时在xml解析后又反转义了一次，文本中的"&amp;"等字面量会变成"&"
+// 读取前把w:t中的&amp;换为私用区字符，xml解析和反转义都不会处理，读取后再换回&
+const AMPERSAND_PLACEHOLDER: char = '\u{E000}';
+
+fn protect_text_ampersands(
+    docx_bytes: &[u8],
+) -> Result<std::borrow::Cow<'_, [u8]>, Box<dyn std::error::Error>> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes))?;
+    let mut document = String::new();
+    std::io::Read::read_to_string(&mut archive.by_name("word/document.xml")?, &mut document)?;
+    let text = Regex::new(r"(<w:t(?:\s[^>]*)?>)([^<]*)(</w:t>)")?;
+    if !text
+        .captures_iter(&document)
+        .any(|captures| captures[2].contains("&amp;"))
+    {
+        return Ok(std::borrow::Cow::Borrowed(docx_bytes));
+    }
+    let document = text.replace_all(&document, |captures: &regex::Captures| {
+        format!(
+            "{}{}{}",
+            &captures[1],
+            captures[2].replace("&amp;", &AMPERSAND_PLACEHOLDER.to_string()),
+            &captures[3]
+        )
+    });
+
+    let mut buf = std::io::Cursor::new(vec![]);
+    let mut writer = zip::ZipWriter::new(&mut buf);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let mut content = vec![];
+        std::io::Read::read_to_end(&mut file, &mut content)?;
+        if name == "word/document.xml" {
+            content = document.as_bytes().to_vec();
+        }
+        writer.start_file(name, options)?;
+        std::io::Write::write_all(&mut writer, &content)?;
+    }
+    writer.finish()?;
+    drop(writer);
+    Ok(std::borrow::Cow::Owned(buf.into_inner()))
+}
+
+// 与docx-rs写入文本前的转义相同
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+        .replace('\n', "&#xA;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn verify_written_docx() {
+        let docx_bytes = pack_to_vec(Docx::new()).unwrap();
+        assert!(verify_docx_bytes(&docx_bytes).is_ok());
+
+        // 替换为无法解析的document.xml
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&docx_bytes)).unwrap();
+        let mut buf = std::io::Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buf);
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index).unwrap();
+            let name = file.name().to_string();
+            let mut content = vec![];
+            std::io::Read::read_to_end(&mut file, &mut content).unwrap();
+            if name == "word/document.xml" {
+                content = br#"<w:document><w:body><w:p><w:r><w:t>a</w:r></w:p>"#.to_vec();
+            }
+            writer
+                .start_file(name, zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(&content).unwrap();
+        }
+        writer.finish().unwrap();
+        drop(writer);
+        assert!(verify_docx_bytes(buf.get_ref()).is_err());
+    }
+
+    #[test]
+    fn keep_media_after_repack() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x01\0\0\0\x01".to_vec();
+        let original = pack_to_vec(Docx::new().add_paragraph(docx_rs::Paragraph::new().add_run(
+            docx_rs::Run::new().add_image(docx_rs::Pic::new_with_dimensions(png.clone(), 1, 1)),
+        )))
+        .unwrap();
+        let media = |docx_bytes: &[u8]| {
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes)).unwrap();
+            let name = archive
+                .file_names()
+                .find(|name| name.starts_with("word/media/") && !name.ends_with('/'))
+                .unwrap()
+                .to_string();
+            let mut content = vec![];
+            std::io::Read::read_to_end(&mut archive.by_name(&name).unwrap(), &mut content).unwrap();
+            content
+        };
+        assert_eq!(media(&original), png);
+
+        // 读取时补回图片内容，写回后图片不丢失
+        let packed = pack_to_vec(read_docx_checked(&original).unwrap()).unwrap();
+        assert_eq!(media(&packed), png);
+        assert!(verify_docx_bytes(&packed).is_ok());
+    }
+
+    #[test]
+    fn reject_non_docx_input() {
+        let error = read_docx_checked("只是一段文本".as_bytes())
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(error, "不是有效的.docx文件（应为ZIP格式）");
+
+        let mut doc = OLE_MAGIC.to_vec();
+        doc.extend([0u8; 16]);
+        assert!(check_docx_bytes(&doc).unwrap_err().contains("旧版.doc"));
+
+        // 普通的zip压缩包
+        let mut buf = std::io::Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buf);
+        writer
+            .start_file("readme.txt", zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+        assert!(
+            check_docx_bytes(buf.get_ref())
+                .unwrap_err()
+                .contains("word/document.xml")
+        );
+
+        let mut buf = std::io::Cursor::new(vec![]);
+        Docx::new().build().pack(&mut buf).unwrap();
+        assert!(read_docx_checked(buf.get_ref()).is_ok());
+    }
+
+    #[test]
+    fn read_and_save_keeps_special_characters() {
+        use crate::sql_parser::cell::paragraph_children_text;
+        use docx_rs::{Paragraph, Run};
+
+        let pack = |docx: Docx| {
+            let mut buf = std::io::Cursor::new(vec![]);
+            docx.build().pack(&mut buf).unwrap();
+            buf.into_inner()
+        };
+        let first = pack(
+            Docx::new()
+                .add_paragraph(Paragraph::new().add_run(Run::new().add_text("A & <B> AT&amp;T"))),
+        );
+
+        // 读取后再保存两次，文本不变且xml有效
+        let second = pack(read_docx_checked(&first).unwrap());
+        let docx = read_docx_checked(&pack(read_docx_checked(&second).unwrap())).unwrap();
+        let DocumentChild::Paragraph(paragraph) = &docx.document.children[0] else {
+            panic!("应为段落");
+        };
+        assert_eq!(
+            paragraph_children_text(&paragraph.children),
+            "A & <B> AT&amp;T"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use std::mem;
+
+use docx_rs::{
+    DocumentChild, Docx, ParagraphChild, Shading, ShdType, Table, TableCell, TableCellContent,
+    TableChild, TableRowChild,
+};
+
+// 文档的配色，颜色为不带#的十六进制
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    // 标题样式的文字颜色，None时不修改
+    pub heading_color: Option<String>,
+    // 表头单元格的底色
+    pub header_fill: String,
+    // 表头单元格的文字颜色
+    pub header_text_color: String,
+}
+
+// 内置的配色名称，default表示使用模板本身的颜色
+pub const THEME_NAMES: &[&str] = &["default", "dark-header", "blue"];
+
+impl Theme {
+    // 内置配色，default或不支持的名称返回None
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "dark-header" => Some(Theme {
+                heading_color: None,
+                header_fill: "333333".to_string(),
+                header_text_color: "FFFFFF".to_string(),
+            }),
+            "blue" => Some(Theme {
+                heading_color: Some("1F4E79".to_string()),
+                header_fill: "2E75B6".to_string(),
+                header_text_color: "FFFFFF".to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    // 使用一个主色：标题和表头底色都用主色，表头文字按主色的亮度选择黑色或白色
+    pub fn primary(color: &str) -> Result<Theme, String> {
+        let color = color.trim_start_matches('#').to_uppercase();
+        if color.len() != 6 || !color.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("颜色格式错误：{color}，应为6位十六进制，如#1F4E79"));
+        }
+        let channel = |index: usize| u32::from_str_radix(&color[index..index + 2], 16).unwrap_or(0);
+        let luminance = channel(0) * 299 + channel(2) * 587 + channel(4) * 114;
+        let header_text_color = if luminance > 128 * 1000 {
+            "000000"
+        } else {
+            "FFFFFF"
+        };
+        Ok(Theme {
+            heading_color: Some(color.clone()),
+            header_fill: color,
+            header_text_color: header_text_color.to_string(),
+        })
+    }
+}
+
+// 修改标题样式的颜色和表头的底色
+// 模板中只有表头单元格设置了底色，有底色（非auto）的单元格都视为表头
+pub fn apply_theme(docx: &mut Docx, theme: &Theme) {
+    if let Some(heading_color) = &theme.heading_color {
+        for style in docx.styles.styles.iter_mut() {
+            if style.name.is_heading() {
+                let run_property = mem::take(&mut style.run_property);
+                style.run_property = run_property.color(heading_color);
+            }
+        }
+    }
+
+    for doc_child in docx.document.children.iter_mut() {
+        if let DocumentChild::Table(table) = doc_child {
+            theme_table(table, theme);
+        }
+    }
+}
+
+fn theme_table(table: &mut Table, theme: &Theme) {
+    for TableChild::TableRow(table_row) in table.rows.iter_mut() {
+        for TableRowChild::TableCell(table_cell) in table_row.cells.iter_mut() {
+            if is_shaded(table_cell) {
+                theme_header_cell(table_cell, theme);
+            }
+            for content in table_cell.children.iter_mut() {
+                if let TableCellContent::Table(table) = content {
+                    theme_table(table, theme);
+                }
+            }
+        }
+    }
+}
+
+// 底色是私有字段，通过序列化读取
+fn is_shaded(table_cell: &TableCell) -> bool {
+    let property_value =
+        serde_json::to_value(&table_cell.property).unwrap_or(serde_json::Value::Null);
+    property_value
+        .get("shading")
+        .and_then(|item| item.get("fill"))
+        .and_then(|item| item.as_str())
+        .is_some_and(|fill| !fill.is_empty() && fill != "auto")
+}
+
+fn theme_header_cell(table_cell: &mut TableCell, theme: &Theme) {
+    fn color_runs(children: &mut [ParagraphChild], color: &str) {
+        for item in children {
+            match item {
+                ParagraphChild::Run(run) => {
+                    let run_property = mem::take(&mut run.run_property);
+                    run.run_property = run_property.color(color);
+                }
+                ParagraphChild::Hyperlink(hyperlink) => color_runs(&mut hyperlink.children, color),
+                _ => {}
+            }
+        }
+    }
+
+    let property = mem::take(&mut table_cell.property);
+    table_cell.property = property.shading(
+        Shading::new()
+            .shd_type(ShdType::Clear)
+            .color("auto")
+            .fill(&theme.header_fill),
+    );
+    for content in table_cell.children.iter_mut() {
+        if let TableCellContent::Paragraph(paragraph) = content {
+            color_runs(&mut paragraph.children, &theme.header_text_color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use docx_rs::{BuildXML, Paragraph, Run, Style, StyleType, TableRow};
+
+    #[test]
+    fn apply_theme_to_headings_and_header_cells() {
+        let cell = |text: &str| {
+            TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)))
+        };
+        let mut docx = Docx::new()
+            .add_style(Style::new("2", StyleType::Paragraph).name("heading 1"))
+            .add_style(Style::new("Body", StyleType::Paragraph).name("Body"))
+            .add_table(Table::new(vec![
+                TableRow::new(vec![cell("参数名").shading(Shading::new().fill("559E68"))]),
+                TableRow::new(vec![cell("id").shading(Shading::new().fill("auto"))]),
+            ]));
+        apply_theme(&mut docx, &Theme::named("blue").unwrap());
+
+        let style_color = |style_id: &str| {
+            let style = docx.styles.find_style_by_id(style_id).unwrap();
+            String::from_utf8(style.run_property.build()).unwrap()
+        };
+        assert!(style_color("2").contains(r#"<w:color w:val="1F4E79" />"#));
+        assert!(!style_color("Body").contains("w:color"));
+
+        let xml = String::from_utf8(docx.document.build()).unwrap();
+        assert!(xml.contains(r#"w:fill="2E75B6""#));
+        assert!(!xml.contains("559E68"));
+        // 只有表头的文字改为白色
+        assert_eq!(xml.matches(r#"<w:color w:val="FFFFFF" />"#).count(), 1);
+    }
+
+    #[test]
+    fn primary_color_picks_readable_header_text() {
+        let theme = Theme::primary("#1f4e79").unwrap();
+        assert_eq!(theme.heading_color.as_deref(), Some("1F4E79"));
+        assert_eq!(theme.header_text_color, "FFFFFF");
+        assert_eq!(
+            Theme::primary("FFE699").unwrap().header_text_color,
+            "000000"
+        );
+        assert!(Theme::primary("blue").is_err());
+        assert_eq!(Theme::named("default"), None);
+    }
+}
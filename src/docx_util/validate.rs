@@ -0,0 +1,140 @@
+use docx_rs::{Document, DocumentChild, TableChild, TableRowChild};
+use serde::Serialize;
+
+// 表格结构问题
+#[derive(Debug, Serialize, Clone)]
+pub struct TableIssue {
+    // 表格在文档中的序号（从0开始，只计算表格）
+    pub table_index: usize,
+
+    // 行号（从0开始）
+    pub row_index: usize,
+
+    // 期望的列数
+    pub expected_columns: usize,
+
+    // 实际的列数（合并单元格按gridSpan计算）
+    pub actual_columns: usize,
+}
+
+impl std::fmt::Display for TableIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "表格{} 第{}行: 期望{}列, 实际{}列",
+            self.table_index, self.row_index, self.expected_columns, self.actual_columns
+        )
+    }
+}
+
+// 检查表格每一行的列数是否一致
+// 期望列数优先取tblGrid的列数，没有tblGrid时取第一行的列数
+pub fn validate_tables(docx: &Document) -> Vec<TableIssue> {
+    let mut issues = vec![];
+    let mut table_index = 0;
+    for doc_child in &docx.children {
+        if let DocumentChild::Table(t_box) = doc_child {
+            let row_columns = t_box
+                .rows
+                .iter()
+                .map(|TableChild::TableRow(table_row)| {
+                    // 使用json读取属性
+                    let property_value: serde_json::Value =
+                        serde_json::to_value(&table_row.property)
+                            .unwrap_or(serde_json::Value::Null);
+                    let grid_before = property_value
+                        .get("gridBefore")
+                        .and_then(|item| item.as_u64())
+                        .unwrap_or(0) as usize;
+                    let grid_after = property_value
+                        .get("gridAfter")
+                        .and_then(|item| item.as_u64())
+                        .unwrap_or(0) as usize;
+
+                    let cell_columns: usize = table_row
+                        .cells
+                        .iter()
+                        .map(|TableRowChild::TableCell(table_cell)| {
+                            serde_json::to_value(&table_cell.property)
+                                .ok()
+                                .and_then(|item| item.get("gridSpan").and_then(|v| v.as_u64()))
+                                .unwrap_or(1) as usize
+                        })
+                        .sum();
+
+                    grid_before + cell_columns + grid_after
+                })
+                .collect::<Vec<usize>>();
+
+            let expected_columns = if t_box.grid.is_empty() {
+                row_columns.first().copied().unwrap_or(0)
+            } else {
+                t_box.grid.len()
+            };
+
+            for (row_index, actual_columns) in row_columns.into_iter().enumerate() {
+                if actual_columns != expected_columns {
+                    issues.push(TableIssue {
+                        table_index,
+                        row_index,
+                        expected_columns,
+                        actual_columns,
+                    });
+                }
+            }
+
+            table_index += 1;
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_parser::test_util::text_cell;
+    use docx_rs::{Docx, Table, TableRow};
+
+    fn issues(docx: &Docx) -> Vec<(usize, usize, usize, usize)> {
+        validate_tables(&docx.document)
+            .into_iter()
+            .map(|issue| {
+                (
+                    issue.table_index,
+                    issue.row_index,
+                    issue.expected_columns,
+                    issue.actual_columns,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn report_rows_with_wrong_column_count() {
+        let docx = Docx::new()
+            // 没有tblGrid时按第一行的列数
+            .add_table(Table::new(vec![
+                TableRow::new(vec![text_cell("a"), text_cell("b"), text_cell("c")]),
+                TableRow::new(vec![text_cell("a").grid_span(2), text_cell("b")]),
+                TableRow::new(vec![text_cell("a"), text_cell("b")]),
+                TableRow::new(vec![text_cell("a"), text_cell("b")]).grid_before(1),
+            ]))
+            // 有tblGrid时按tblGrid的列数，第一行也会检查
+            .add_table(
+                Table::new(vec![
+                    TableRow::new(vec![text_cell("a"), text_cell("b"), text_cell("c")]),
+                    TableRow::new(vec![text_cell("a").grid_span(3), text_cell("b")]),
+                    TableRow::new(vec![text_cell("a"), text_cell("b")]).grid_after(2),
+                    TableRow::new(vec![text_cell("a").grid_span(4), text_cell("b")]),
+                ])
+                .set_grid(vec![1000, 1000, 1000, 1000]),
+            );
+
+        assert_eq!(
+            issues(&docx),
+            vec![(0, 2, 3, 2), (1, 0, 4, 3), (1, 3, 4, 5)]
+        );
+        assert!(validate_tables(&Docx::new().document).is_empty());
+    }
+}
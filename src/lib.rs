@@ -1 +1,4 @@
-pub mod sql_parser;
\ No newline at end of file
+pub mod docx_to_html;
+pub mod docx_util;
+pub mod sql_parser;
+pub mod swagger;
@@ -1,114 +1,1108 @@
 use base64::{Engine, engine::general_purpose};
 use bytes::Bytes;
-use clap::{Arg, Command};
+use chrono::Local;
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use docx_handlebars::render_handlebars;
+use docx_tool::docx_to_html::{convert_docx, docx_to_html, extract_text};
+use docx_tool::docx_util::analyze::analyze_bytes;
+use docx_tool::docx_util::find::find_cells_regex;
+use docx_tool::docx_util::fingerprint::document_fingerprint;
+use docx_tool::docx_util::image::{images_to_base64, to_word_image};
+use docx_tool::docx_util::insert::duplicate_row;
+use docx_tool::docx_util::link::list_hyperlinks;
+use docx_tool::docx_util::merge::{append_document, prefix_style_ids};
+use docx_tool::docx_util::read::{
+    check_docx_bytes, pack_to_vec, read_docx_checked, verify_docx_bytes,
+};
+use docx_tool::docx_util::theme::{THEME_NAMES, Theme, apply_theme};
+use docx_tool::docx_util::validate::validate_tables;
+use docx_tool::sql_parser::{DocxDb, PlannedChange, core_properties::CoreProperties};
+use docx_tool::swagger::*;
+use futures::executor::block_on;
+use gluesql::prelude::{Glue, Key, Payload};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::swagger::*;
-
-mod docx_to_html;
-mod swagger;
+mod open;
+mod watch;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let matches = Command::new("docx-tools")
         .about("根据json和docx模板生成目标docx的工具")
-        .arg(
-            Arg::new("swagger")
-                .long("swagger")
-                .value_parser(clap::value_parser!(String))
-                .help("基于swagger生成接口文档，目前支持swagger 2.0"),
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("swagger")
+                .about("基于swagger生成接口文档，目前支持swagger 2.0")
+                .arg(
+                    Arg::new("swagger")
+                        .required(true)
+                        .value_parser(clap::value_parser!(String))
+                        .help("swagger文件的路径或url"),
+                )
+                .arg(
+                    Arg::new("lang")
+                        .long("lang")
+                        .value_parser(["zh", "en"])
+                        .default_value("zh")
+                        .help("swagger文档的模板语言"),
+                )
+                .arg(
+                    Arg::new("flatten-style")
+                        .long("flatten-style")
+                        .value_parser(["json", "dot"])
+                        .default_value("json")
+                        .help("嵌套字段名的拼接方式，json: a.b[].c，dot: a.b.[].c"),
+                )
+                .arg(
+                    Arg::new("max-depth")
+                        .long("max-depth")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10")
+                        .help("swagger引用展开的最大深度"),
+                )
+                .arg(
+                    Arg::new("strip-html-descriptions")
+                        .long("strip-html-descriptions")
+                        .action(ArgAction::SetTrue)
+                        .help("移除swagger说明中的html标签"),
+                )
+                .arg(
+                    Arg::new("since")
+                        .long("since")
+                        .value_parser(clap::value_parser!(String))
+                        .help("只生成x-since不小于该版本的接口，按semver比较"),
+                )
+                .arg(
+                    Arg::new("include-unversioned")
+                        .long("include-unversioned")
+                        .requires("since")
+                        .action(ArgAction::SetTrue)
+                        .help("使用--since时保留没有x-since的接口"),
+                )
+                .arg(
+                    Arg::new("include-tags")
+                        .long("include-tags")
+                        .action(ArgAction::Append)
+                        .value_parser(clap::value_parser!(String))
+                        .help("只生成这些分组，可以指定多次；与--exclude-tags同时指定时以此为准"),
+                )
+                .arg(
+                    Arg::new("exclude-tags")
+                        .long("exclude-tags")
+                        .action(ArgAction::Append)
+                        .value_parser(clap::value_parser!(String))
+                        .help("不生成这些分组，可以指定多次"),
+                )
+                .arg(
+                    Arg::new("group-by")
+                        .long("group-by")
+                        .value_parser(["tag", "path"])
+                        .default_value("tag")
+                        .help("接口的分组方式，tag: 按tags分组，path: 按路径的第一段分组"),
+                )
+                .arg(
+                    Arg::new("with-models")
+                        .long("with-models")
+                        .action(ArgAction::SetTrue)
+                        .help("在文档末尾生成数据模型附录，列出每个definition的字段"),
+                )
+                .arg(
+                    Arg::new("empty-placeholder")
+                        .long("empty-placeholder")
+                        .value_parser(clap::value_parser!(String))
+                        .help("空的说明、数据类型和认证使用的占位符，如\"-\""),
+                )
+                .arg(
+                    Arg::new("number-sections")
+                        .long("number-sections")
+                        .action(ArgAction::SetTrue)
+                        .help("给分组和接口编号，如\"1.3 创建用户\""),
+                )
+                .arg(
+                    Arg::new("realistic-examples")
+                        .long("realistic-examples")
+                        .action(ArgAction::SetTrue)
+                        .help("按字段名生成不同的整数和布尔示例值，默认整数为1、布尔为false"),
+                )
+                .arg(
+                    Arg::new("array-item-types")
+                        .long("array-item-types")
+                        .action(ArgAction::SetTrue)
+                        .help("数组参数的类型显示元素类型，如array<string>"),
+                )
+                .arg(
+                    Arg::new("xml-examples")
+                        .long("xml-examples")
+                        .action(ArgAction::SetTrue)
+                        .help("接口只返回xml时，按字段生成xml格式的返回示例"),
+                )
+                .arg(
+                    Arg::new("theme")
+                        .long("theme")
+                        .value_parser(THEME_NAMES.to_vec())
+                        .help("文档配色，修改标题颜色和表头底色，default使用模板的颜色"),
+                )
+                .arg(
+                    Arg::new("primary-color")
+                        .long("primary-color")
+                        .conflicts_with("theme")
+                        .help("自定义主色，如#1F4E79，用于标题颜色和表头底色"),
+                )
+                .arg(
+                    Arg::new("no-timestamp")
+                        .long("no-timestamp")
+                        .action(ArgAction::SetTrue)
+                        .help("不记录生成时间，相同输入生成的文档内容相同"),
+                )
+                .arg(
+                    Arg::new("cover")
+                        .long("cover")
+                        .action(ArgAction::SetTrue)
+                        .help("在正文前生成封面，包括项目名称、版本和生成时间"),
+                )
+                .arg(
+                    Arg::new("logo")
+                        .long("logo")
+                        .requires("cover")
+                        .value_parser(clap::value_parser!(String))
+                        .help("封面上的logo图片路径或url"),
+                )
+                .arg(
+                    Arg::new("append-to")
+                        .long("append-to")
+                        .value_parser(clap::value_parser!(String))
+                        .help("把生成的接口文档追加到该docx的正文后面，样式和编号以该文件为准合并"),
+                )
+                .arg(
+                    Arg::new("style-prefix")
+                        .long("style-prefix")
+                        .requires("append-to")
+                        .value_parser(clap::value_parser!(String))
+                        .help("追加前给接口文档的样式id加上前缀，避免与--append-to文档中的同名样式冲突"),
+                )
+                .arg(
+                    Arg::new("csv")
+                        .long("csv")
+                        .value_parser(clap::value_parser!(String))
+                        .help("同时把接口清单导出为csv，每个接口一行"),
+                )
+                .arg(
+                    Arg::new("fail-on-empty")
+                        .long("fail-on-empty")
+                        .action(ArgAction::SetTrue)
+                        .help("解析后没有任何接口时不生成文档，并以非0状态退出"),
+                )
+                .arg(
+                    Arg::new("export-schemas")
+                        .long("export-schemas")
+                        .value_parser(clap::value_parser!(String))
+                        .help("把definitions导出为JSON Schema文件到该目录，每个定义一个文件"),
+                )
+                .arg(
+                    Arg::new("dump-model")
+                        .long("dump-model")
+                        .action(ArgAction::SetTrue)
+                        .help("输出解析后的模板对象json"),
+                )
+                .args(json_style_args())
+                .arg(output_arg("输出文件名，按扩展名输出docx、html、md或txt"))
+                .arg(verify_arg())
+                .arg(watch_arg())
+                .arg(open_arg()),
+        )
+        .subcommand(
+            Command::new("render")
+                .about("使用json数据渲染docx模板")
+                .arg(
+                    Arg::new("docx-model")
+                        .long("model")
+                        .required_unless_present("manifest")
+                        .value_parser(clap::value_parser!(String))
+                        .help("docx的模板路径"),
+                )
+                .arg(
+                    Arg::new("model-json")
+                        .long("json")
+                        .required_unless_present("manifest")
+                        .value_parser(clap::value_parser!(String))
+                        .help("docx的模板填充的json数据文件路径"),
+                )
+                .arg(
+                    Arg::new("manifest")
+                        .long("manifest")
+                        .value_parser(clap::value_parser!(String))
+                        .conflicts_with_all(["docx-model", "model-json", "output", "watch"])
+                        .help(
+                            "批量渲染的清单json，格式为[{\"template\": \"\", \"json\": \"\", \"output\": \"\"}]",
+                        ),
+                )
+                .arg(
+                    Arg::new("concurrency")
+                        .long("concurrency")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("4")
+                        .help("同时读取远程文件（如图片）的最大数量，批量渲染时也是同时渲染的文档数"),
+                )
+                .arg(output_arg("输出文件名，按扩展名输出docx、html、md或txt"))
+                .arg(verify_arg())
+                .arg(watch_arg())
+                .arg(open_arg()),
+        )
+        .subcommand(
+            Command::new("html")
+                .about("把docx转为html")
+                .arg(docx_arg())
+                .arg(output_arg("输出文件名，不指定时输出到控制台"))
+                .arg(open_arg()),
+        )
+        .subcommand(
+            Command::new("extract")
+                .about("提取docx的纯文本")
+                .arg(docx_arg())
+                .arg(output_arg("输出文件名，不指定时输出到控制台"))
+                .arg(open_arg()),
+        )
+        .subcommand(
+            Command::new("sql")
+                .about("对docx执行sql")
+                .arg(docx_arg())
+                .arg(
+                    Arg::new("sql")
+                        .required(true)
+                        .value_parser(clap::value_parser!(String))
+                        .help("要执行的sql"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["text", "json"])
+                        .default_value("text")
+                        .help("sql查询结果的输出格式"),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .action(ArgAction::SetTrue)
+                        .help("只打印将要修改的行数和修改示例，不修改、不保存文档"),
+                )
+                .arg(
+                    Arg::new("trim-content")
+                        .long("trim-content")
+                        .action(ArgAction::SetTrue)
+                        .help("写入cell的content时去掉首尾空白，合并中间连续的空白"),
+                )
+                .arg(
+                    Arg::new("xml-content")
+                        .long("xml-content")
+                        .action(ArgAction::SetTrue)
+                        .help("生成tables和cell的xml_content列，默认为null，避免每次扫描都序列化整个表格"),
+                )
+                .args(json_style_args())
+                .arg(output_arg("有修改时保存的文件名"))
+                .arg(verify_arg()),
         )
-        .arg(
-            Arg::new("docx-model")
-                .long("model")
-                .value_parser(clap::value_parser!(String))
-                .help("docx的模板路径"),
+        .subcommand(
+            Command::new("find")
+                .about("查找文本匹配正则的单元格")
+                .arg(docx_arg())
+                .arg(
+                    Arg::new("pattern")
+                        .required(true)
+                        .value_parser(clap::value_parser!(String))
+                        .help("正则表达式"),
+                ),
         )
-        .arg(
-            Arg::new("model-json")
-                .long("json")
-                .value_parser(clap::value_parser!(String))
-                .help("docx的模板填充的json数据文件路径"),
+        .subcommand(
+            Command::new("validate")
+                .about("检查docx中表格每行的列数是否一致")
+                .arg(docx_arg()),
         )
-        .arg(
-            Arg::new("output")
-                .long("output")
-                .value_parser(clap::value_parser!(String))
-                .help("输出文件名"),
+        .subcommand(
+            Command::new("analyze")
+                .about("列出docx中sql和html暂不支持的内容")
+                .arg(docx_arg()),
+        )
+        .subcommand(
+            Command::new("links")
+                .about("列出docx中的超链接")
+                .arg(docx_arg())
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .action(ArgAction::SetTrue)
+                        .help("访问每个http(s)链接，报告无法访问的链接"),
+                ),
+        )
+        .subcommand(
+            Command::new("fingerprint")
+                .about("输出docx内容的指纹，忽略创建、修改时间等元数据")
+                .arg(docx_arg()),
+        )
+        .subcommand(
+            Command::new("duplicate-row")
+                .about("把表格中的一行复制多份，插入到该行之后，用于按模板行填充数据")
+                .arg(docx_arg())
+                .arg(
+                    Arg::new("table-hash")
+                        .long("table-hash")
+                        .required(true)
+                        .value_parser(clap::value_parser!(String))
+                        .help("表格的哈希，与sql中tables表的hash列一致"),
+                )
+                .arg(
+                    Arg::new("row")
+                        .long("row")
+                        .required(true)
+                        .value_parser(clap::value_parser!(usize))
+                        .help("要复制的行号，从0开始"),
+                )
+                .arg(
+                    Arg::new("times")
+                        .long("times")
+                        .default_value("1")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("复制的份数"),
+                )
+                .arg(
+                    Arg::new("clear")
+                        .long("clear")
+                        .action(ArgAction::SetTrue)
+                        .help("清空复制出的cell的文本，保留格式"),
+                )
+                .arg(output_arg("输出文件名，默认为output.docx"))
+                .arg(verify_arg()),
+        )
+        .subcommand(
+            Command::new("concat")
+                .about("按顺序拼接多个docx的正文，样式和编号以第一个文件为准合并")
+                .arg(
+                    Arg::new("docx")
+                        .required(true)
+                        .num_args(2..)
+                        .value_parser(clap::value_parser!(String))
+                        .help("docx文件的路径或url"),
+                )
+                .arg(output_arg("输出文件名，默认为output.docx"))
+                .arg(verify_arg())
+                .arg(open_arg()),
         )
         .get_matches();
 
-    let mut output_file_name: String = "output.docx".to_string();
-    if let Some(output) = matches.get_one::<String>("output") {
-        output_file_name = output.clone();
+    match matches.subcommand() {
+        Some(("swagger", sub_matches)) => {
+            watch_or_generate(sub_matches, &["swagger"], generate_swagger)
+        }
+        Some(("render", sub_matches)) => match sub_matches.get_one::<String>("manifest") {
+            Some(manifest_path) => render_manifest(sub_matches, manifest_path),
+            None => watch_or_generate(sub_matches, &["docx-model", "model-json"], generate_render),
+        },
+        Some(("html", sub_matches)) => {
+            let docx = read_docx_checked(&get_file_bytes(docx_path(sub_matches))?)?;
+            print_or_write(sub_matches, docx_to_html(&docx))
+        }
+        Some(("extract", sub_matches)) => {
+            let docx = read_docx_checked(&get_file_bytes(docx_path(sub_matches))?)?;
+            print_or_write(sub_matches, extract_text(&docx))
+        }
+        Some(("sql", sub_matches)) => run_sql(sub_matches),
+        Some(("find", sub_matches)) => {
+            // 按正则查找单元格
+            let docx = read_docx_checked(&get_file_bytes(docx_path(sub_matches))?)?;
+            let pattern = sub_matches
+                .get_one::<String>("pattern")
+                .ok_or("缺少正则表达式")?;
+            for cell in find_cells_regex(&docx.document, pattern)? {
+                println!("{cell}");
+            }
+            Ok(())
+        }
+        Some(("validate", sub_matches)) => {
+            // 检查表格结构
+            let docx = read_docx_checked(&get_file_bytes(docx_path(sub_matches))?)?;
+            let issues = validate_tables(&docx.document);
+            if issues.is_empty() {
+                println!("表格结构正常");
+            }
+            for issue in issues {
+                println!("{issue}");
+            }
+            Ok(())
+        }
+        Some(("analyze", sub_matches)) => {
+            // 列出不支持的内容
+            let report = analyze_bytes(&get_file_bytes(docx_path(sub_matches))?)?;
+            if report.is_empty() {
+                println!("没有发现不支持的内容");
+            }
+            for item in report {
+                println!("{item}");
+            }
+            Ok(())
+        }
+        Some(("links", sub_matches)) => {
+            let docx = read_docx_checked(&get_file_bytes(docx_path(sub_matches))?)?;
+            let links = list_hyperlinks(&docx);
+            if links.is_empty() {
+                println!("没有超链接");
+            }
+            if !sub_matches.get_flag("check") {
+                for link in links {
+                    println!("{link}");
+                }
+                return Ok(());
+            }
+
+            // 检查外部链接，相同地址只访问一次
+            let mut checked: HashMap<String, Option<String>> = HashMap::new();
+            let mut dead = 0;
+            for link in links.iter().filter(|item| item.is_external()) {
+                let error = checked
+                    .entry(link.target.clone())
+                    .or_insert_with(|| get_file_bytes(&link.target).err().map(|e| e.to_string()));
+                match error {
+                    Some(e) => {
+                        dead += 1;
+                        println!("[失效] {link}, {e}");
+                    }
+                    None => println!("[正常] {link}"),
+                }
+            }
+            if dead > 0 {
+                return Err(format!("{dead}个链接无法访问").into());
+            }
+            Ok(())
+        }
+        Some(("fingerprint", sub_matches)) => {
+            let docx = read_docx_checked(&get_file_bytes(docx_path(sub_matches))?)?;
+            println!("{}", document_fingerprint(&docx));
+            Ok(())
+        }
+        Some(("duplicate-row", sub_matches)) => {
+            let mut docx = read_docx_checked(&get_file_bytes(docx_path(sub_matches))?)?;
+            let table_hash = sub_matches
+                .get_one::<String>("table-hash")
+                .ok_or("缺少表格哈希")?;
+            let row = *sub_matches.get_one::<usize>("row").ok_or("缺少行号")?;
+            let times = *sub_matches.get_one::<usize>("times").unwrap_or(&1);
+            let inserted = duplicate_row(
+                &mut docx.document,
+                table_hash,
+                row,
+                times,
+                sub_matches.get_flag("clear"),
+            );
+            if inserted == 0 {
+                return Err("没有找到表格或行".into());
+            }
+            println!("插入{inserted}行");
+            let docx_bytes = pack_to_vec(docx)?;
+            verify_output(sub_matches, "复制表格行", &docx_bytes)?;
+            std::fs::write(output_file_name(sub_matches), docx_bytes)?;
+            Ok(())
+        }
+        Some(("concat", sub_matches)) => {
+            let mut paths = sub_matches.get_many::<String>("docx").unwrap_or_default();
+            let mut docx =
+                read_docx_checked(&get_file_bytes(paths.next().ok_or("缺少docx文件")?)?)?;
+            for path in paths {
+                append_document(&mut docx, &read_docx_checked(&get_file_bytes(path)?)?);
+            }
+            let output_file_name = output_file_name(sub_matches);
+            let docx_bytes = pack_to_vec(docx)?;
+            verify_output(sub_matches, "拼接文档", &docx_bytes)?;
+            std::fs::write(&output_file_name, docx_bytes)?;
+            open_output(sub_matches, &output_file_name);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+// 子命令共用的参数
+fn docx_arg() -> Arg {
+    Arg::new("docx")
+        .required(true)
+        .value_parser(clap::value_parser!(String))
+        .help("docx文件的路径或url")
+}
+
+fn output_arg(help: &'static str) -> Arg {
+    Arg::new("output")
+        .long("output")
+        .value_parser(clap::value_parser!(String))
+        .help(help)
+}
+
+fn open_arg() -> Arg {
+    Arg::new("open")
+        .long("open")
+        .action(ArgAction::SetTrue)
+        .help("生成后使用系统默认程序打开输出文件")
+}
+
+fn verify_arg() -> Arg {
+    Arg::new("verify")
+        .long("verify")
+        .action(ArgAction::SetTrue)
+        .help("保存前重新读取生成的docx，无法解析时报错且不保存")
+}
+
+// 指定--verify时重新读取生成的docx，operation用于提示可能出错的操作
+fn verify_output(
+    matches: &ArgMatches,
+    operation: &str,
+    docx_bytes: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    if matches.get_flag("verify") {
+        verify_docx_bytes(docx_bytes)
+            .map_err(|e| format!("校验失败：{operation}生成的docx无法重新读取, {e}"))?;
     }
+    Ok(())
+}
+
+// 指定--open时打开输出文件
+fn open_output(matches: &ArgMatches, output_file_name: &str) {
+    if matches.get_flag("open") {
+        open::open_file(output_file_name);
+    }
+}
+
+fn watch_arg() -> Arg {
+    Arg::new("watch")
+        .long("watch")
+        .action(ArgAction::SetTrue)
+        .help("监听输入文件，变化后重新生成；每500毫秒轮询修改时间，连续修改停止300毫秒后触发")
+}
+
+// 输出json时是否缩进，都不指定时使用各命令的默认值
+fn json_style_args() -> [Arg; 2] {
+    [
+        Arg::new("pretty")
+            .long("pretty")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("compact")
+            .help("输出缩进的json"),
+        Arg::new("compact")
+            .long("compact")
+            .action(ArgAction::SetTrue)
+            .help("输出紧凑的json，便于管道处理"),
+    ]
+}
+
+fn to_json_string(
+    matches: &ArgMatches,
+    value: &impl Serialize,
+    default_pretty: bool,
+) -> serde_json::Result<String> {
+    let pretty = matches.get_flag("pretty") || (default_pretty && !matches.get_flag("compact"));
+    if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+}
+
+fn docx_path(matches: &ArgMatches) -> &str {
+    matches
+        .get_one::<String>("docx")
+        .map(|item| item.as_str())
+        .unwrap_or("")
+}
+
+fn output_file_name(matches: &ArgMatches) -> String {
+    matches
+        .get_one::<String>("output")
+        .cloned()
+        .unwrap_or("output.docx".to_string())
+}
+
+// 有--output时写入文件，否则输出到控制台
+fn print_or_write(matches: &ArgMatches, content: String) -> Result<(), Box<dyn std::error::Error>> {
+    match matches.get_one::<String>("output") {
+        Some(output) => {
+            std::fs::write(output, content)?;
+            open_output(matches, output);
+        }
+        None => println!("{content}"),
+    }
+    Ok(())
+}
+
+// 根据子命令参数生成文档并保存到指定文件
+type GenerateFn = fn(&ArgMatches, &str) -> Result<(), Box<dyn std::error::Error>>;
+
+// 生成一次，指定--watch时监听inputs对应的文件，变化后重新生成
+fn watch_or_generate(
+    matches: &ArgMatches,
+    inputs: &[&str],
+    generate: GenerateFn,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output_file_name = output_file_name(matches);
+    if !matches.get_flag("watch") {
+        generate(matches, &output_file_name)?;
+        open_output(matches, &output_file_name);
+        return Ok(());
+    }
+
+    let inputs: Vec<String> = inputs
+        .iter()
+        .filter_map(|id| matches.get_one::<String>(id))
+        .cloned()
+        .collect();
+    let regenerate = || {
+        let now = Local::now().format("%Y-%m-%d %H:%M:%S");
+        match generate(matches, &output_file_name) {
+            Ok(_) => println!("[{now}] 已生成 {output_file_name}"),
+            Err(e) => println!("[{now}] 生成失败, {e}"),
+        }
+    };
+    regenerate();
+    // 只在第一次生成后打开，之后由打开文件的程序自行刷新
+    open_output(matches, &output_file_name);
+    watch::watch_files(&inputs, regenerate);
+
+    Ok(())
+}
 
-    // 解析swagger并生成文档
-    if let Some(swagger_path) = matches.get_one::<String>("swagger") {
-        let swagger_bytes = get_file_bytes(&swagger_path)?;
+// 对docx执行sql，有修改时保存到--output
+fn run_sql(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let sql = matches.get_one::<String>("sql").ok_or("缺少sql")?;
+    let docx_bytes = get_file_bytes(docx_path(matches))?;
+    let mut docx = read_docx_checked(&docx_bytes)?;
+    let dry_run = matches.get_flag("dry-run");
+    let (payloads, planned) = {
+        let db = DocxDb::new(&mut docx)
+            .core_properties(CoreProperties::from_docx_bytes(&docx_bytes))
+            .dry_run(dry_run)
+            .trim_content(matches.get_flag("trim-content"))
+            .xml_content(matches.get_flag("xml-content"));
+        let mut glue = Glue::new(db);
+        let payloads = block_on(glue.execute(sql))?;
+        (payloads, glue.storage.planned)
+    };
 
-        // 生成docx文件
-        let r = parse_swagger_and_gen_docx(&swagger_bytes, &output_file_name);
-        if let Err(e) = r {
-            println!("parse_swagger_and_gen_docx fail, {e:?}");
+    let json_format = matches.get_one::<String>("format").map(|f| f.as_str()) == Some("json");
+    let mut modified = false;
+    for payload in &payloads {
+        if payload.select().is_none() {
+            modified = true;
+        }
+        match payload_to_json(payload) {
+            Some(rows) if json_format => println!("{}", to_json_string(matches, &rows, false)?),
+            _ => println!("{payload:?}"),
         }
+    }
 
+    if dry_run {
+        print_planned_changes(&planned);
         return Ok(());
     }
 
-    // 通用的模板
-    if let Some(model_path) = matches.get_one::<String>("docx-model") {
-        if let Some(json_path) = matches.get_one::<String>("model-json") {
-            let template_bytes = get_file_bytes(&model_path)?;
-            let json_bytes = get_file_bytes(&json_path)?;
-            let mut value: Value = serde_json::from_slice(&json_bytes)?;
+    // 有修改时保存
+    if modified {
+        let docx_bytes = pack_to_vec(docx)?;
+        verify_output(matches, &format!("sql（{sql}）"), &docx_bytes)?;
+        std::fs::write(output_file_name(matches), docx_bytes)?;
+    }
 
-            // 处理图片路径
-            image_to_base64(&mut value);
+    Ok(())
+}
 
-            // 渲染模板
-            // println!("{}", serde_json::to_string_pretty(&value)?);
-            let result = render_handlebars(template_bytes, &value)?;
+// 打印dry-run的统计和前几行的修改示例
+fn print_planned_changes(planned: &[PlannedChange]) {
+    const PREVIEW_ROWS: usize = 5;
 
-            // 保存
-            std::fs::write(output_file_name, result)?;
+    let mut counts: Vec<(&str, usize)> = vec![];
+    for change in planned {
+        match counts
+            .iter_mut()
+            .find(|(table_name, _)| *table_name == change.table_name)
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&change.table_name, 1)),
+        }
+    }
+    if counts.is_empty() {
+        println!("[dry-run] 没有行会被修改");
+        return;
+    }
+    let summary = counts
+        .iter()
+        .map(|(table_name, count)| format!("{table_name} {count}行"))
+        .collect::<Vec<_>>()
+        .join("，");
+    println!("[dry-run] 将修改: {summary}");
 
-            return Ok(());
+    for change in planned.iter().take(PREVIEW_ROWS) {
+        let key = match &change.key {
+            Key::Str(key) => key.chars().take(8).collect(),
+            key => format!("{key:?}"),
+        };
+        for (name, before, after) in &change.columns {
+            println!(
+                "  {} {key}: {name} {:?} → {:?}",
+                change.table_name,
+                String::from(before),
+                String::from(after)
+            );
         }
     }
+    if planned.len() > PREVIEW_ROWS {
+        println!("  ...其余{}行省略", planned.len() - PREVIEW_ROWS);
+    }
+}
+
+// 解析swagger并生成文档
+fn generate_swagger(
+    matches: &ArgMatches,
+    output_file_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let swagger_path = matches
+        .get_one::<String>("swagger")
+        .ok_or("缺少swagger文件")?;
+    let swagger_bytes = get_file_bytes(swagger_path)?;
+    let mut options = SwaggerOptions::default();
+    if let Some(lang) = matches.get_one::<String>("lang") {
+        options.lang = lang.clone();
+    }
+    if let Some(flatten_style) = matches.get_one::<String>("flatten-style") {
+        options.flatten_style = if flatten_style == "dot" {
+            FlattenStyle::Dot
+        } else {
+            FlattenStyle::Json
+        };
+    }
+    if let Some(max_depth) = matches.get_one::<usize>("max-depth") {
+        options.max_depth = *max_depth;
+    }
+    options.strip_html_descriptions = matches.get_flag("strip-html-descriptions");
+    options.since = matches.get_one::<String>("since").cloned();
+    options.include_unversioned = matches.get_flag("include-unversioned");
+    options.include_tags = matches
+        .get_many::<String>("include-tags")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    options.exclude_tags = matches
+        .get_many::<String>("exclude-tags")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    if let Some(group_by) = matches.get_one::<String>("group-by") {
+        options.group_by = if group_by == "path" {
+            GroupBy::Path
+        } else {
+            GroupBy::Tag
+        };
+    }
+    options.with_models = matches.get_flag("with-models");
+    options.empty_placeholder = matches.get_one::<String>("empty-placeholder").cloned();
+    options.number_sections = matches.get_flag("number-sections");
+    options.realistic_examples = matches.get_flag("realistic-examples");
+    options.array_item_types = matches.get_flag("array-item-types");
+    options.xml_examples = matches.get_flag("xml-examples");
+    options.source = Some(swagger_path.clone());
+    options.timestamp = !matches.get_flag("no-timestamp");
+    options.cover = matches.get_flag("cover");
+    if let Some(logo_path) = matches.get_one::<String>("logo") {
+        let content = to_word_image(logo_path, get_file_bytes(logo_path)?);
+        options.logo = Some(general_purpose::STANDARD.encode(&content));
+    }
+    // 配色，default使用模板本身的颜色
+    let theme = match matches.get_one::<String>("primary-color") {
+        Some(color) => Some(Theme::primary(color)?),
+        None => matches
+            .get_one::<String>("theme")
+            .and_then(|name| Theme::named(name)),
+    };
+
+    // 导出JSON Schema
+    if let Some(dir) = matches.get_one::<String>("export-schemas") {
+        export_schemas(&swagger_bytes, dir)?;
+    }
+
+    // 解析时发现的问题输出到stderr，不影响--dump-model的输出
+    let (docx_project, diagnostics) = parse_swagger_with_diagnostics(&swagger_bytes, &options);
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+    if let Some(error) = diagnostics
+        .iter()
+        .find(|item| item.severity == Severity::Error)
+    {
+        return Err(error.message.clone().into());
+    }
+    // 没有接口时不生成文档
+    if matches.get_flag("fail-on-empty") && docx_project.endpoint_count() == 0 {
+        let reason = match &options.since {
+            Some(since) => format!("所有接口都被--since {since}过滤"),
+            None => "paths为空或接口都没有生成".to_string(),
+        };
+        return Err(format!("swagger中没有可生成的接口：{reason}").into());
+    }
+
+    // 生成docx文件
+    if matches.get_flag("dump-model") {
+        println!("{}", to_json_string(matches, &docx_project, true)?);
+    }
+    // 带BOM，Excel打开时中文不乱码
+    if let Some(csv_path) = matches.get_one::<String>("csv") {
+        std::fs::write(
+            csv_path,
+            format!("\u{feff}{}", project_to_csv(&docx_project)),
+        )?;
+    }
+    let mut result = render_swagger_docx(&docx_project, &options.lang)?;
+    if let Some(theme) = &theme {
+        result = apply_theme_to_docx(&result, theme)?;
+    }
+    if let Some(base_path) = matches.get_one::<String>("append-to") {
+        result = append_to_base(
+            base_path,
+            &result,
+            matches.get_one::<String>("style-prefix"),
+        )?;
+        verify_output(matches, "追加到已有文档", &result)?;
+    } else {
+        verify_output(matches, "swagger生成", &result)?;
+    }
+    write_output(output_file_name, result)
+}
+
+// 渲染后修改docx的配色
+fn apply_theme_to_docx(
+    docx_bytes: &[u8],
+    theme: &Theme,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut docx = read_docx_checked(docx_bytes)?;
+    apply_theme(&mut docx, theme);
+    pack_to_vec(docx)
+}
+
+// 把docx追加到base后面，返回合并后的docx
+fn append_to_base(
+    base_path: &str,
+    docx_bytes: &[u8],
+    style_prefix: Option<&String>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut base = read_docx_checked(&get_file_bytes(base_path)?)?;
+    let mut docx = read_docx_checked(docx_bytes)?;
+    if let Some(prefix) = style_prefix {
+        prefix_style_ids(&mut docx, prefix);
+    }
+    append_document(&mut base, &docx);
+    pack_to_vec(base)
+}
+
+// 导出definitions的JSON Schema，每个定义写入"定义名.json"，文件名中的特殊字符替换为_
+fn export_schemas(swagger_bytes: &[u8], dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let schemas = export_json_schemas(swagger_bytes)?;
+    std::fs::create_dir_all(dir)?;
+    for (name, schema) in &schemas {
+        let file_name = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        std::fs::write(
+            Path::new(dir).join(format!("{file_name}.json")),
+            serde_json::to_string_pretty(schema)?,
+        )?;
+    }
+    println!("已导出{}个JSON Schema到{dir}", schemas.len());
 
     Ok(())
 }
 
-fn image_to_base64(value: &mut Value) {
-    match value {
-        Value::Object(map) => {
-            let mut add_items: HashMap<String, Value> = HashMap::new();
-            for (k, v) in map.iter_mut() {
-                if k.ends_with(".image") {
-                    if let Value::String(map_value) = v {
-                        let content = get_file_bytes(map_value).unwrap_or(vec![]);
-                        *v = Value::String(general_purpose::STANDARD.encode(&content));
-                        add_items.insert(
-                            k.strip_suffix(".image").unwrap_or(k).to_string(),
-                            Value::String(general_purpose::STANDARD.encode(&content)),
-                        );
+// 通用的模板
+fn generate_render(
+    matches: &ArgMatches,
+    output_file_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let model_path = matches
+        .get_one::<String>("docx-model")
+        .ok_or("缺少--model")?;
+    let json_path = matches
+        .get_one::<String>("model-json")
+        .ok_or("缺少--json")?;
+    let concurrency = *matches.get_one::<usize>("concurrency").unwrap_or(&4);
+    render_template(
+        model_path,
+        json_path,
+        output_file_name,
+        concurrency,
+        matches.get_flag("verify"),
+    )
+}
+
+// 使用json数据渲染模板并保存
+fn render_template(
+    model_path: &str,
+    json_path: &str,
+    output_file_name: &str,
+    concurrency: usize,
+    verify: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let template_bytes = get_file_bytes(model_path)?;
+    check_docx_bytes(&template_bytes)?;
+    let json_bytes = get_file_bytes(json_path)?;
+    let mut value: Value = serde_json::from_slice(&json_bytes)?;
+
+    // 处理图片路径
+    let vectors = images_to_base64(&mut value, |paths| get_files_bytes(paths, concurrency));
+
+    // 渲染模板
+    // println!("{}", serde_json::to_string_pretty(&value)?);
+    let result = vectors.restore(render_handlebars(template_bytes, &value)?)?;
+    if verify {
+        verify_docx_bytes(&result)
+            .map_err(|e| format!("校验失败：模板{model_path}渲染后的docx无法重新读取, {e}"))?;
+    }
+
+    // 保存
+    write_output(output_file_name, result)?;
+
+    Ok(())
+}
+
+// 批量渲染清单中的一项
+#[derive(Debug, Deserialize)]
+struct ManifestEntry {
+    template: String,
+    json: String,
+    output: String,
+}
+
+// 按清单批量渲染，单个文档失败时继续渲染其他文档，最后汇总结果
+fn render_manifest(
+    matches: &ArgMatches,
+    manifest_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let concurrency = *matches.get_one::<usize>("concurrency").unwrap_or(&4);
+    let entries: Vec<ManifestEntry> = serde_json::from_slice(&get_file_bytes(manifest_path)?)?;
+
+    let next_index = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.max(1).min(entries.len()) {
+            scope.spawn(|| {
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::SeqCst);
+                    let Some(entry) = entries.get(index) else {
+                        break;
+                    };
+                    // 错误转为字符串，Box<dyn Error>不能跨线程
+                    let result = render_template(
+                        &entry.template,
+                        &entry.json,
+                        &entry.output,
+                        concurrency,
+                        matches.get_flag("verify"),
+                    )
+                    .map_err(|e| e.to_string());
+                    match result {
+                        Ok(_) => println!("[成功] {}", entry.output),
+                        Err(e) => {
+                            failed.fetch_add(1, Ordering::SeqCst);
+                            println!("[失败] {}, {e}", entry.output);
+                        }
                     }
                 }
-                image_to_base64(v);
-            }
-            // 添加
-            map.extend(add_items);
-        }
-        Value::Array(arr) => {
-            for ele in arr {
-                image_to_base64(ele);
-            }
+            });
         }
-        _ => {}
+    });
+
+    let failed = failed.into_inner();
+    println!(
+        "共{}个文档，成功{}个，失败{}个",
+        entries.len(),
+        entries.len() - failed,
+        failed
+    );
+    if failed > 0 {
+        return Err(format!("{failed}个文档生成失败").into());
     }
+
+    Ok(())
+}
+
+fn write_output(
+    output_file_name: &str,
+    docx_bytes: Vec<u8>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let extension = Path::new(output_file_name)
+        .extension()
+        .and_then(|item| item.to_str())
+        .unwrap_or("");
+    let content = match convert_docx(&docx_bytes, extension)? {
+        Some(text) => text.into_bytes(),
+        None => docx_bytes,
+    };
+    std::fs::write(output_file_name, content)?;
+
+    Ok(())
+}
+
+// 将select结果转为json数组，每行一个 列名->值 的对象
+fn payload_to_json(payload: &Payload) -> Option<Value> {
+    let rows = payload
+        .select()?
+        .map(|row| {
+            row.into_iter()
+                .map(|(label, value)| {
+                    let value = Value::try_from(value.clone()).unwrap_or(Value::Null);
+                    (label.to_string(), value)
+                })
+                .collect::<serde_json::Map<String, Value>>()
+        })
+        .map(Value::Object)
+        .collect();
+
+    Some(Value::Array(rows))
+}
+
+// 文件读取结果，错误转为字符串以便跨线程传递
+type FileBytesResult = Result<Vec<u8>, String>;
+
+// 并发读取多个文件，最多同时读取concurrency个，结果顺序与paths一致
+fn get_files_bytes(paths: &[String], concurrency: usize) -> Vec<FileBytesResult> {
+    let next_index = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<FileBytesResult>>> = Mutex::new(vec![None; paths.len()]);
+
+    // ureq是阻塞的，使用线程读取
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.max(1).min(paths.len()) {
+            scope.spawn(|| {
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::SeqCst);
+                    if index >= paths.len() {
+                        break;
+                    }
+                    let result = get_file_bytes(&paths[index]).map_err(|e| e.to_string());
+                    if let Ok(mut results) = results.lock() {
+                        results[index] = Some(result);
+                    }
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|item| item.unwrap_or(Err("读取失败".to_string())))
+        .collect()
 }
 
 fn get_file_bytes(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
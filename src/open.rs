@@ -0,0 +1,29 @@
+use std::{path::Path, process::Command};
+
+// 使用系统默认程序打开生成的文件，失败时只打印警告
+pub fn open_file(path: &str) {
+    if !Path::new(path).exists() {
+        println!("警告: {path} 不存在，无法打开");
+        return;
+    }
+    // 没有图形界面时不打开
+    if cfg!(target_os = "linux")
+        && std::env::var_os("DISPLAY").is_none()
+        && std::env::var_os("WAYLAND_DISPLAY").is_none()
+    {
+        println!("警告: 没有图形界面，不打开 {path}");
+        return;
+    }
+
+    let result = if cfg!(target_os = "windows") {
+        // start的第一个带引号的参数是窗口标题
+        Command::new("cmd").args(["/C", "start", "", path]).spawn()
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(path).spawn()
+    } else {
+        Command::new("xdg-open").arg(path).spawn()
+    };
+    if let Err(e) = result {
+        println!("警告: 打开 {path} 失败, {e}");
+    }
+}
@@ -2,11 +2,13 @@ use std::{collections::HashMap, iter, str::FromStr};
 
 use async_trait::async_trait;
 use docx_rs::{
-    BorderType, Document, DocumentChild, Docx, Justification, Paragraph, ParagraphChild, RunChild,
-    TableAlignmentType, TableCellBorder, TableCellBorderPosition, TableCellContent,
-    TableCellProperty, TableChild, TableRowChild, WidthType, border_position, read_docx,
+    AlignmentType, BorderType, BuildXML, Document, DocumentChild, Docx, HyperlinkData, InsertChild,
+    Justification, Paragraph, ParagraphChild, Run, RunChild, RunProperty, Table,
+    TableAlignmentType, TableCell, TableCellBorder, TableCellBorderPosition, TableCellContent,
+    TableCellProperty, TableChild, TableRowChild, TextDirectionType, VAlignType, WidthType,
+    border_position, read_docx,
 };
-use futures::stream::{self, StreamExt};
+use futures::stream;
 use gluesql::{
     core::{
         ast::ColumnDef,
@@ -14,11 +16,12 @@ use gluesql::{
         error::FetchError,
         store::{
             AlterTable, CustomFunction, CustomFunctionMut, DataRow, Index, IndexMut, Metadata,
-            RowIter, Store, StoreMut, Transaction,
+            RowIter, StoreMut, Transaction,
         },
     },
     prelude::{DataType, Error, Key, Result},
 };
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::mem;
 
@@ -57,13 +60,63 @@ impl Cell {
                     unique: None,
                     comment: Some("cell内容".to_string()),
                 },
+                ColumnDef {
+                    name: "content_normalized".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some(
+                        "去掉首尾空白、连续空白合并为一个空格后的cell内容，只读".to_string(),
+                    ),
+                },
+                ColumnDef {
+                    name: "links".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("cell中超链接地址的json数组".to_string()),
+                },
+                ColumnDef {
+                    name: "justification".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("段落对齐方式，空字符串表示继承".to_string()),
+                },
+                ColumnDef {
+                    name: "text_rotation".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some(
+                        "文字旋转角度，0、90或270，旋转时同时设置垂直居中和段落居中".to_string(),
+                    ),
+                },
+                ColumnDef {
+                    name: "highlight".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some(
+                        "文字突出显示颜色，如yellow、green、cyan，空字符串表示无，与底纹不同"
+                            .to_string(),
+                    ),
+                },
                 ColumnDef {
                     name: "width".to_string(),
                     data_type: DataType::Uint32,
                     nullable: true,
                     default: None,
                     unique: None,
-                    comment: Some("表格宽度".to_string()),
+                    comment: Some(
+                        "单元格宽度，dxa以1/20磅为单位，pct以1/50个百分点为单位（0~100按百分比换算）"
+                            .to_string(),
+                    ),
                 },
                 ColumnDef {
                     name: "width_type".to_string(),
@@ -71,7 +124,7 @@ impl Cell {
                     nullable: true,
                     default: None,
                     unique: None,
-                    comment: Some("表格宽度类型".to_string()),
+                    comment: Some("单元格宽度类型，dxa、pct、auto或nil".to_string()),
                 },
                 ColumnDef {
                     name: "borders_top".to_string(),
@@ -129,6 +182,17 @@ impl Cell {
                     unique: None,
                     comment: Some("cell的json形式".to_string()),
                 },
+                ColumnDef {
+                    name: "xml_content".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some(
+                        "cell序列化后的OOXML（<w:tc>），只读，DocxDb开启xml_content时才生成"
+                            .to_string(),
+                    ),
+                },
             ]),
             indexes: vec![],
             engine: None,
@@ -137,14 +201,17 @@ impl Cell {
         }]
     }
 
-    pub async fn fetch_data(&self, docx: &Document, key: &Key) -> Result<Option<DataRow>> {
-        // 查找
-        if let Ok(mut rowIter) = self.scan_data(docx).await {
-            while let Some(row_result) = rowIter.next().await {
-                if let Ok(row) = row_result {
-                    if row.0 == *key {
-                        return Ok(Some(row.1.clone()));
-                    }
+    pub async fn fetch_data(
+        &self,
+        docx: &Document,
+        key: &Key,
+        xml_content: bool,
+    ) -> Result<Option<DataRow>> {
+        // 只计算hash，命中后再读取其它列
+        for t_box in tables(docx) {
+            for table_cell in table_cells(t_box) {
+                if Key::Str(json_hash(table_cell)) == *key {
+                    return Ok(Some(cell_row(&json_hash(t_box), table_cell, xml_content).1));
                 }
             }
         }
@@ -152,159 +219,27 @@ impl Cell {
         return Result::Ok(None);
     }
 
-    // todo 修改为stream格式
-    pub async fn scan_data<'a>(&self, docx: &Document) -> Result<RowIter<'a>> {
-        let mut cells = Vec::new();
-        for doc_child in &docx.children {
-            if let DocumentChild::Table(t_box) = doc_child {
-                let table_json_str = serde_json::to_string(t_box).unwrap_or("".to_string());
-                let mut hasher = Sha256::new();
-                hasher.update(table_json_str.as_bytes());
-                let result = hasher.finalize();
-                let table_hash_hex = hex::encode(result);
-
-                // 遍历cell
-                for row in &t_box.rows {
-                    if let TableChild::TableRow(table_row) = row {
-                        for cell in &table_row.cells {
-                            if let TableRowChild::TableCell(table_cell) = cell {
-                                // cell的文本内容
-                                let runs = table_cell
-                                    .children
-                                    .iter()
-                                    .flat_map(|item: &TableCellContent| {
-                                        if let TableCellContent::Paragraph(paragraph) = item {
-                                            paragraph.children.iter()
-                                        } else {
-                                            [].iter()
-                                        }
-                                    })
-                                    .flat_map(|item| {
-                                        if let ParagraphChild::Run(run) = item {
-                                            run.children.iter()
-                                        } else {
-                                            [].iter()
-                                        }
-                                    })
-                                    .map(|item| {
-                                        if let RunChild::Text(run_text) = item {
-                                            run_text.text.clone()
-                                        } else {
-                                            "".to_string()
-                                        }
-                                    })
-                                    .collect::<Vec<String>>();
-                                let content = runs.join("");
-
-                                let table_json_str =
-                                    serde_json::to_string(table_cell).unwrap_or("".to_string());
-                                let mut hasher = Sha256::new();
-                                hasher.update(table_json_str.as_bytes());
-                                let result = hasher.finalize();
-                                let cell_hash_hex = hex::encode(result);
-
-                                // 使用json读取属性
-                                let property_value: serde_json::Value =
-                                    serde_json::to_value(&table_cell.property)
-                                        .unwrap_or(serde_json::Value::Null);
-
-                                let key = Key::Str(cell_hash_hex.clone());
-                                let mut hm: HashMap<String, Value> = HashMap::new();
-                                hm.insert("hash".to_string(), Value::Str(cell_hash_hex.clone()));
-                                hm.insert(
-                                    "table_hash".to_string(),
-                                    Value::Str(table_hash_hex.clone()),
-                                );
-                                hm.insert("content".to_string(), Value::Str(content.clone()));
-                                hm.insert(
-                                    "width".to_string(),
-                                    Value::U32(
-                                        property_value
-                                            .get("width")
-                                            .and_then(|item| item.get("width"))
-                                            .and_then(|item| item.as_u64())
-                                            .and_then(|item| Some(item as u32))
-                                            .unwrap_or(0u32),
-                                    ),
-                                );
-                                hm.insert(
-                                    "width_type".to_string(),
-                                    Value::Str(
-                                        property_value
-                                            .get("width")
-                                            .and_then(|item| item.get("widthType"))
-                                            .and_then(|item| item.as_str())
-                                            .unwrap_or("")
-                                            .to_string(),
-                                    ),
-                                );
-                                hm.insert(
-                                    "borders_top".to_string(),
-                                    property_value
-                                        .get("borders")
-                                        .and_then(|item| item.get("top"))
-                                        .and_then(|item| item.as_str())
-                                        .map(|item| Value::Str(item.to_string()))
-                                        .unwrap_or(Value::Null),
-                                );
-                                hm.insert(
-                                    "borders_left".to_string(),
-                                    property_value
-                                        .get("borders")
-                                        .and_then(|item| item.get("left"))
-                                        .and_then(|item| item.as_str())
-                                        .map(|item| Value::Str(item.to_string()))
-                                        .unwrap_or(Value::Null),
-                                );
-                                hm.insert(
-                                    "borders_bottom".to_string(),
-                                    property_value
-                                        .get("borders")
-                                        .and_then(|item| item.get("bottom"))
-                                        .and_then(|item| item.as_str())
-                                        .map(|item| Value::Str(item.to_string()))
-                                        .unwrap_or(Value::Null),
-                                );
-                                hm.insert(
-                                    "borders_right".to_string(),
-                                    property_value
-                                        .get("borders")
-                                        .and_then(|item| item.get("right"))
-                                        .and_then(|item| item.as_str())
-                                        .map(|item| Value::Str(item.to_string()))
-                                        .unwrap_or(Value::Null),
-                                );
-                                hm.insert(
-                                    "borders_inside_h".to_string(),
-                                    property_value
-                                        .get("borders")
-                                        .and_then(|item| item.get("insideH"))
-                                        .and_then(|item| item.as_str())
-                                        .map(|item| Value::Str(item.to_string()))
-                                        .unwrap_or(Value::Null),
-                                );
-                                hm.insert(
-                                    "borders_inside_v".to_string(),
-                                    property_value
-                                        .get("borders")
-                                        .and_then(|item| item.get("insideV"))
-                                        .and_then(|item| item.as_str())
-                                        .map(|item| Value::Str(item.to_string()))
-                                        .unwrap_or(Value::Null),
-                                );
-
-                                let data_row = DataRow::Map(hm);
-                                cells.push(Ok((key, data_row)));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    // gluesql不会传入查询的列，这里按需生成每行，只读取部分行时不会处理整个文档
+    pub async fn scan_data<'a>(
+        &self,
+        docx: &'a Document,
+        xml_content: bool,
+    ) -> Result<RowIter<'a>> {
+        let cells = tables(docx).flat_map(move |t_box| {
+            let table_hash_hex = json_hash(t_box);
+            table_cells(t_box)
+                .map(move |table_cell| Ok(cell_row(&table_hash_hex, table_cell, xml_content)))
+        });
         return Ok(Box::pin(stream::iter(cells)));
     }
 
-    pub async fn insert_data(&self, docx: &mut Document, _rows: Vec<(Key, DataRow)>) -> Result<()> {
+    // trim_content为true时，写入的content先去掉首尾空白并合并每行中间连续的空白
+    pub async fn insert_data(
+        &self,
+        docx: &mut Document,
+        _rows: Vec<(Key, DataRow)>,
+        trim_content: bool,
+    ) -> Result<()> {
         // 查找
         for doc_child in &mut docx.children {
             if let DocumentChild::Table(t_box) = doc_child {
@@ -322,127 +257,12 @@ impl Cell {
                             let hash_key = Key::Str(cell_hash_hex);
 
                             for row in &_rows {
-                                if row.0 == hash_key {
-                                    if let DataRow::Map(kvs) = &row.1 {
-                                        for kv in kvs.iter() {
-                                            if kv.0 == "width" {
-                                                if let Value::U32(width) = kv.1 {
-                                                    // 使用json读取属性
-                                                    let property_value: serde_json::Value =
-                                                        serde_json::to_value(&table_cell.property)
-                                                            .unwrap_or(serde_json::Value::Null);
-                                                    let pre_width = property_value
-                                                        .get("width")
-                                                        .and_then(|item| item.get("width"))
-                                                        .and_then(|item| item.as_u64())
-                                                        .and_then(|item| Some(item as usize))
-                                                        .unwrap_or(0usize);
-                                                    let pre_width_type = property_value
-                                                        .get("width")
-                                                        .and_then(|item| item.get("widthType"))
-                                                        .and_then(|item| item.as_str())
-                                                        .and_then(|item| {
-                                                            WidthType::from_str(item).ok()
-                                                        })
-                                                        .unwrap_or(WidthType::Unsupported);
-
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property = property
-                                                        .width(*width as usize, pre_width_type);
-                                                }
-                                            }
-                                            if kv.0 == "width_type" {
-                                                if let Value::Str(width_type) = kv.1 {
-                                                    // 使用json读取属性
-                                                    let property_value: serde_json::Value =
-                                                        serde_json::to_value(&table_cell.property)
-                                                            .unwrap_or(serde_json::Value::Null);
-                                                    let pre_width = property_value
-                                                        .get("width")
-                                                        .and_then(|item| item.get("width"))
-                                                        .and_then(|item| item.as_u64())
-                                                        .and_then(|item| Some(item as usize))
-                                                        .unwrap_or(0usize);
-                                                    let pre_width_type =
-                                                        WidthType::from_str(width_type)
-                                                            .ok()
-                                                            .unwrap_or(WidthType::Auto);
-
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property =
-                                                        property.width(pre_width, pre_width_type);
-                                                }
-                                            }
-                                            if kv.0 == "borders_top" {
-                                                if let Value::Str(border_value) = kv.1 {
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property = self.set_border(
-                                                        property,
-                                                        border_value,
-                                                        TableCellBorderPosition::Top,
-                                                    );
-                                                }
-                                            }
-                                            if kv.0 == "borders_left" {
-                                                if let Value::Str(border_value) = kv.1 {
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property = self.set_border(
-                                                        property,
-                                                        border_value,
-                                                        TableCellBorderPosition::Left,
-                                                    );
-                                                }
-                                            }
-                                            if kv.0 == "borders_bottom" {
-                                                if let Value::Str(border_value) = kv.1 {
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property = self.set_border(
-                                                        property,
-                                                        border_value,
-                                                        TableCellBorderPosition::Bottom,
-                                                    );
-                                                }
-                                            }
-                                            if kv.0 == "borders_right" {
-                                                if let Value::Str(border_value) = kv.1 {
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property = self.set_border(
-                                                        property,
-                                                        border_value,
-                                                        TableCellBorderPosition::Right,
-                                                    );
-                                                }
-                                            }
-                                            if kv.0 == "borders_inside_h" {
-                                                if let Value::Str(border_value) = kv.1 {
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property = self.set_border(
-                                                        property,
-                                                        border_value,
-                                                        TableCellBorderPosition::InsideH,
-                                                    );
-                                                }
-                                            }
-                                            if kv.0 == "borders_inside_v" {
-                                                if let Value::Str(border_value) = kv.1 {
-                                                    let property =
-                                                        mem::take(&mut table_cell.property);
-                                                    table_cell.property = self.set_border(
-                                                        property,
-                                                        border_value,
-                                                        TableCellBorderPosition::InsideV,
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    }
+                                if row.0 == hash_key
+                                    && let DataRow::Map(kvs) = &row.1
+                                {
+                                    // 先收集所有列的修改，校验通过后再修改cell
+                                    CellUpdate::new(table_cell, kvs, trim_content)?
+                                        .apply(table_cell);
                                 }
                             }
                         }
@@ -464,31 +284,1162 @@ impl Cell {
         let value: serde_json::Value =
             serde_json::from_str(&border_value).unwrap_or(serde_json::Value::Null);
 
+        // 当前位置已有的边框，只覆盖传入的属性
+        let position_key =
+            serde_json::to_value(&border_position).unwrap_or(serde_json::Value::Null);
+        let current = serde_json::to_value(&property)
+            .ok()
+            .and_then(|item| {
+                item.get("borders")
+                    .and_then(|borders| borders.get(position_key.as_str().unwrap_or("")))
+                    .cloned()
+            })
+            .unwrap_or(serde_json::Value::Null);
+
         let mut table_border = TableCellBorder::new(border_position);
+        for value in [current, value] {
+            // 颜色
+            if let Some(color) = value.get("color").and_then(|item| item.as_str()) {
+                table_border = table_border.color(color);
+            }
 
-        // 颜色
-        if let Some(color) = value.get("color").and_then(|item| item.as_str()) {
-            table_border = table_border.color(color);
+            // 线条宽度
+            if let Some(size) = value
+                .get("size")
+                .and_then(|item| item.as_u64())
+                .and_then(|item| Some(item as usize))
+            {
+                table_border = table_border.size(size);
+            }
+
+            // 线条类型
+            if let Some(border_type) = value
+                .get("borderType")
+                .and_then(|item| item.as_str())
+                .and_then(|item| BorderType::from_str(item).ok())
+            {
+                table_border = table_border.border_type(border_type);
+            }
         }
 
-        // 线条宽度
-        if let Some(size) = value
-            .get("size")
-            .and_then(|item| item.as_u64())
-            .and_then(|item| Some(item as usize))
+        return property.set_border(table_border);
+    }
+}
+
+// 边框列和对应的位置，按此顺序修改
+const BORDER_COLUMNS: [(&str, TableCellBorderPosition); 6] = [
+    ("borders_top", TableCellBorderPosition::Top),
+    ("borders_left", TableCellBorderPosition::Left),
+    ("borders_bottom", TableCellBorderPosition::Bottom),
+    ("borders_right", TableCellBorderPosition::Right),
+    ("borders_inside_h", TableCellBorderPosition::InsideH),
+    ("borders_inside_v", TableCellBorderPosition::InsideV),
+];
+
+// update中一行对cell的修改，除边框外只包含与读取的值不同的列
+// 先收集所有列再按固定顺序修改，结果与列在HashMap中的顺序无关
+#[derive(Default)]
+struct CellUpdate {
+    borders: Vec<(TableCellBorderPosition, String)>,
+    content: Option<String>,
+    highlight: Option<String>,
+    justification: Option<String>,
+    text_rotation: Option<u32>,
+    // 宽度和宽度类型一起换算
+    width: Option<(usize, WidthType)>,
+}
+
+impl CellUpdate {
+    fn new(
+        table_cell: &TableCell,
+        kvs: &HashMap<String, Value>,
+        trim_content: bool,
+    ) -> Result<CellUpdate> {
+        let mut update = CellUpdate::default();
+        for (column, position) in BORDER_COLUMNS {
+            if let Some(Value::Str(border_value)) = kvs.get(column) {
+                update.borders.push((position, border_value.clone()));
+            }
+        }
+        if let Some(Value::Str(content)) = kvs.get("content")
+            && *content != cell_text(table_cell)
+        {
+            update.content = Some(if trim_content {
+                trim_lines(content)
+            } else {
+                content.clone()
+            });
+        }
+        if let Some(Value::Str(highlight)) = kvs.get("highlight")
+            && *highlight != cell_highlight(table_cell)
         {
-            table_border = table_border.size(size);
+            update.highlight = Some(highlight.clone());
+        }
+        if let Some(Value::Str(justification)) = kvs.get("justification")
+            && *justification != cell_justification(table_cell)
+        {
+            update.justification = Some(justification.clone());
+        }
+        if let Some(Value::U32(rotation)) = kvs.get("text_rotation")
+            && *rotation != cell_text_rotation(table_cell)
+        {
+            update.text_rotation = Some(*rotation);
+        }
+
+        // 只传入其中一个时另一个使用当前值
+        let (width, width_type) = cell_width(table_cell);
+        let new_width = match kvs.get("width") {
+            Some(Value::U32(new_width)) => *new_width,
+            _ => width,
+        };
+        let new_width_type = match kvs.get("width_type") {
+            Some(Value::Str(new_width_type)) => new_width_type.clone(),
+            _ => width_type.clone(),
+        };
+        if (new_width, &new_width_type) != (width, &width_type) {
+            update.width =
+                Some(convert_width(new_width, &new_width_type).map_err(Error::StorageMsg)?);
+        }
+
+        Ok(update)
+    }
+
+    // 文字旋转会覆盖对齐方式，放在对齐方式之后
+    fn apply(self, table_cell: &mut TableCell) {
+        if !self.borders.is_empty() {
+            let mut property = mem::take(&mut table_cell.property);
+            for (position, border_value) in self.borders {
+                property = Cell.set_border(property, &border_value, position);
+            }
+            table_cell.property = property;
+        }
+        if let Some(content) = self.content {
+            set_cell_text(table_cell, &content);
+        }
+        if let Some(highlight) = self.highlight {
+            set_cell_highlight(table_cell, &highlight);
+        }
+        if let Some(justification) = self.justification {
+            set_cell_justification(table_cell, &justification);
+        }
+        if let Some(rotation) = self.text_rotation {
+            set_cell_text_rotation(table_cell, rotation);
+        }
+        if let Some((width, width_type)) = self.width {
+            let property = mem::take(&mut table_cell.property);
+            table_cell.property = property.width(width, width_type);
+        }
+    }
+}
+
+// 文档中的所有表格
+pub(crate) fn tables(docx: &Document) -> impl Iterator<Item = &Table> {
+    docx.children.iter().filter_map(|doc_child| {
+        if let DocumentChild::Table(t_box) = doc_child {
+            Some(t_box.as_ref())
+        } else {
+            None
+        }
+    })
+}
+
+// 遍历时的cell，提供位置和常用的修改方法
+pub struct CellContext<'a> {
+    // 表格在正文中的序号，与tables表的顺序相同
+    pub table_index: usize,
+    pub row_index: usize,
+    // cell在行中的序号，横向合并的cell只算一个
+    pub column_index: usize,
+    pub cell: &'a mut TableCell,
+}
+
+impl CellContext<'_> {
+    // 与cell表的content相同
+    pub fn text(&self) -> String {
+        cell_text(self.cell)
+    }
+
+    // 与更新cell表的content相同，按换行拆分为段落
+    pub fn set_text(&mut self, content: &str) {
+        set_cell_text(self.cell, content);
+    }
+
+    // border为json，如{"color":"FF0000","size":8}，只覆盖传入的属性
+    pub fn set_border(&mut self, position: TableCellBorderPosition, border: &str) {
+        let property = mem::take(&mut self.cell.property);
+        self.cell.property = Cell.set_border(property, &border.to_string(), position);
+    }
+}
+
+// 按cell表的顺序遍历正文表格中的所有cell，不经过gluesql直接修改
+pub fn for_each_cell<F: FnMut(CellContext)>(docx: &mut Document, mut f: F) {
+    let tables = docx.children.iter_mut().filter_map(|doc_child| {
+        if let DocumentChild::Table(t_box) = doc_child {
+            Some(t_box.as_mut())
+        } else {
+            None
+        }
+    });
+    for (table_index, t_box) in tables.enumerate() {
+        for (row_index, TableChild::TableRow(table_row)) in t_box.rows.iter_mut().enumerate() {
+            for (column_index, TableRowChild::TableCell(table_cell)) in
+                table_row.cells.iter_mut().enumerate()
+            {
+                f(CellContext {
+                    table_index,
+                    row_index,
+                    column_index,
+                    cell: table_cell,
+                });
+            }
+        }
+    }
+}
+
+// 表格中的所有cell
+fn table_cells(t_box: &Table) -> impl Iterator<Item = &TableCell> {
+    t_box
+        .rows
+        .iter()
+        .flat_map(|TableChild::TableRow(table_row)| table_row.cells.iter())
+        .map(|TableRowChild::TableCell(table_cell)| table_cell)
+}
+
+// json序列化后的sha256
+pub(crate) fn json_hash<T: Serialize>(value: &T) -> String {
+    let json_str = serde_json::to_string(value).unwrap_or("".to_string());
+    let mut hasher = Sha256::new();
+    hasher.update(json_str.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+// cell中所有段落的文本，段落之间换行，嵌套表格的单元格之间使用制表符，行之间换行
+pub(crate) fn cell_text(table_cell: &TableCell) -> String {
+    table_cell
+        .children
+        .iter()
+        .map(|item: &TableCellContent| match item {
+            TableCellContent::Paragraph(paragraph) => paragraph_children_text(&paragraph.children),
+            TableCellContent::Table(table) => table
+                .rows
+                .iter()
+                .map(|TableChild::TableRow(table_row)| {
+                    table_row
+                        .cells
+                        .iter()
+                        .map(|TableRowChild::TableCell(cell)| cell_text(cell))
+                        .collect::<Vec<String>>()
+                        .join("\t")
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+            _ => "".to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// 规范化空白：去掉首尾空白，连续的空白（空格、制表符、换行、全角空格、不换行空格等）合并为一个空格
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// 去掉首尾的空白，每行中间连续的空白合并为一个半角空格，保留换行（段落）
+fn trim_lines(text: &str) -> String {
+    text.trim()
+        .split('\n')
+        .map(normalize_whitespace)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// cell对应的一行数据，xml_content为false时不生成xml_content列
+fn cell_row(table_hash_hex: &str, table_cell: &TableCell, xml_content: bool) -> (Key, DataRow) {
+    // cell的文本内容
+    let content = cell_text(table_cell);
+
+    // cell中的超链接
+    let mut links: Vec<String> = vec![];
+    for item in &table_cell.children {
+        if let TableCellContent::Paragraph(paragraph) = item {
+            paragraph_children_links(&paragraph.children, &mut links);
+        }
+    }
+
+    let cell_hash_hex = json_hash(table_cell);
+
+    // 使用json读取属性
+    let property_value: serde_json::Value =
+        serde_json::to_value(&table_cell.property).unwrap_or(serde_json::Value::Null);
+
+    let key = Key::Str(cell_hash_hex.clone());
+    let mut hm: HashMap<String, Value> = HashMap::new();
+    hm.insert("hash".to_string(), Value::Str(cell_hash_hex.clone()));
+    hm.insert(
+        "table_hash".to_string(),
+        Value::Str(table_hash_hex.to_string()),
+    );
+    hm.insert(
+        "content_normalized".to_string(),
+        Value::Str(normalize_whitespace(&content)),
+    );
+    hm.insert("content".to_string(), Value::Str(content.clone()));
+    hm.insert(
+        "links".to_string(),
+        if links.is_empty() {
+            Value::Null
+        } else {
+            Value::Str(serde_json::to_string(&links).unwrap_or("".to_string()))
+        },
+    );
+    hm.insert(
+        "justification".to_string(),
+        Value::Str(cell_justification(table_cell)),
+    );
+    hm.insert(
+        "text_rotation".to_string(),
+        Value::U32(cell_text_rotation(table_cell)),
+    );
+    hm.insert(
+        "highlight".to_string(),
+        Value::Str(cell_highlight(table_cell)),
+    );
+    let (width, width_type) = cell_width(table_cell);
+    hm.insert("width".to_string(), Value::U32(width));
+    hm.insert("width_type".to_string(), Value::Str(width_type));
+    hm.insert(
+        "borders_top".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("top"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_left".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("left"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_bottom".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("bottom"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_right".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("right"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_inside_h".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("insideH"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_inside_v".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("insideV"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "xml_content".to_string(),
+        if xml_content {
+            Value::Str(String::from_utf8_lossy(&table_cell.build()).to_string())
+        } else {
+            Value::Null
+        },
+    );
+
+    (key, DataRow::Map(hm))
+}
+
+// cell的对齐方式，取第一个段落显式设置的值，没有设置时为空字符串（继承）
+fn cell_justification(table_cell: &TableCell) -> String {
+    table_cell
+        .children
+        .iter()
+        .find_map(|item| match item {
+            TableCellContent::Paragraph(paragraph) => Some(paragraph),
+            _ => None,
+        })
+        .and_then(|paragraph| paragraph.property.alignment.as_ref())
+        .map(|item| item.val.clone())
+        .unwrap_or("".to_string())
+}
+
+// 设置cell中所有段落的对齐方式，空字符串时移除显式设置
+fn set_cell_justification(table_cell: &mut TableCell, justification: &str) {
+    let alignment = if justification.is_empty() {
+        None
+    } else {
+        AlignmentType::from_str(justification)
+            .ok()
+            .map(|item| Justification::new(item.to_string()))
+    };
+    for item in &mut table_cell.children {
+        if let TableCellContent::Paragraph(paragraph) = item {
+            paragraph.property.alignment = alignment.clone();
+        }
+    }
+}
+
+// 替换cell的文本，按换行拆分为多个段落，第n行沿用第n个段落的段落属性和第一个run的属性
+// 行数多于原段落数时，新增的段落沿用最后一个段落的属性；少于时删除多余的段落
+pub(crate) fn set_cell_text(table_cell: &mut TableCell, content: &str) {
+    let lines = content.split('\n').collect::<Vec<&str>>();
+    let line_run = |line: &str, run_property: Option<RunProperty>| {
+        let mut run = Run::new().add_text(line);
+        if let Some(run_property) = run_property {
+            run.run_property = run_property;
+        }
+        run
+    };
+    let first_run_property = |paragraph: &Paragraph| {
+        paragraph.children.iter().find_map(|item| match item {
+            ParagraphChild::Run(run) => Some(run.run_property.clone()),
+            _ => None,
+        })
+    };
+
+    // 按位置替换已有段落的内容，删除多余的段落
+    let mut paragraph_index = 0;
+    let mut last: Option<(usize, Paragraph)> = None;
+    let mut index = 0;
+    table_cell.children.retain_mut(|item| {
+        let keep = match item {
+            TableCellContent::Paragraph(paragraph) => {
+                let keep = paragraph_index < lines.len();
+                if keep {
+                    let run = line_run(lines[paragraph_index], first_run_property(paragraph));
+                    paragraph.children = vec![ParagraphChild::Run(Box::new(run))];
+                    last = Some((index, paragraph.clone()));
+                }
+                paragraph_index += 1;
+                keep
+            }
+            _ => true,
+        };
+        if keep {
+            index += 1;
         }
+        keep
+    });
+
+    // 补充缺少的段落，放在最后一个段落之后
+    let (mut insert_index, template) = match last {
+        Some((last_index, paragraph)) => (last_index + 1, paragraph),
+        None => (table_cell.children.len(), Paragraph::new()),
+    };
+    for line in lines.iter().skip(paragraph_index.min(lines.len())) {
+        let mut paragraph = Paragraph::new();
+        paragraph.property = template.property.clone();
+        paragraph = paragraph.add_run(line_run(line, first_run_property(&template)));
+        table_cell
+            .children
+            .insert(insert_index, TableCellContent::Paragraph(paragraph));
+        insert_index += 1;
+    }
+}
 
-        // 线条类型
-        if let Some(border_type) = value
-            .get("borderType")
+// cell的宽度和宽度类型，没有设置宽度时类型为空字符串
+fn cell_width(table_cell: &TableCell) -> (u32, String) {
+    // 使用json读取属性
+    let width = serde_json::to_value(&table_cell.property)
+        .ok()
+        .and_then(|item| item.get("width").cloned())
+        .unwrap_or(serde_json::Value::Null);
+    (
+        width
+            .get("width")
+            .and_then(|item| item.as_u64())
+            .unwrap_or(0) as u32,
+        width
+            .get("widthType")
             .and_then(|item| item.as_str())
-            .and_then(|item| BorderType::from_str(item).ok())
+            .unwrap_or("")
+            .to_string(),
+    )
+}
+
+// 把输入的宽度换算为docx中的值
+// dxa以1/20磅（twip）为单位，1厘米约567；没有类型时按dxa处理
+// pct以1/50个百分点为单位（5000为100%），0~100按百分比自动乘以50，超过5000时报错
+pub(crate) fn convert_width(
+    width: u32,
+    width_type: &str,
+) -> std::result::Result<(usize, WidthType), String> {
+    let width = width as usize;
+    match width_type {
+        "" | "dxa" | "unsupported" => Ok((width, WidthType::Dxa)),
+        "auto" => Ok((width, WidthType::Auto)),
+        "nil" => Ok((width, WidthType::Nil)),
+        "pct" => match width {
+            0..=100 => Ok((width * 50, WidthType::Pct)),
+            101..=5000 => Ok((width, WidthType::Pct)),
+            _ => Err(format!(
+                "[Storage] pct宽度{width}超出范围，应为0~100（百分比）或0~5000（1/50个百分点）"
+            )),
+        },
+        _ => Err(format!(
+            "[Storage] 不支持的宽度类型{width_type}，应为dxa、pct、auto或nil"
+        )),
+    }
+}
+
+// Word支持的突出显示颜色
+const HIGHLIGHT_COLORS: [&str; 16] = [
+    "black",
+    "blue",
+    "cyan",
+    "green",
+    "magenta",
+    "red",
+    "yellow",
+    "white",
+    "darkBlue",
+    "darkCyan",
+    "darkGreen",
+    "darkMagenta",
+    "darkRed",
+    "darkYellow",
+    "darkGray",
+    "lightGray",
+];
+
+// cell中第一个run的突出显示颜色，没有时为空字符串
+fn cell_highlight(table_cell: &TableCell) -> String {
+    fn first_run(children: &[ParagraphChild]) -> Option<&Run> {
+        children.iter().find_map(|item| match item {
+            ParagraphChild::Run(run) => Some(run.as_ref()),
+            ParagraphChild::Hyperlink(hyperlink) => first_run(&hyperlink.children),
+            _ => None,
+        })
+    }
+    table_cell
+        .children
+        .iter()
+        .find_map(|item| match item {
+            TableCellContent::Paragraph(paragraph) => first_run(&paragraph.children),
+            _ => None,
+        })
+        .and_then(|run| serde_json::to_value(&run.run_property.highlight).ok())
+        .and_then(|item| item.as_str().map(|item| item.to_string()))
+        .unwrap_or("".to_string())
+}
+
+// 设置cell中所有run的突出显示，空字符串或none时移除，不支持的颜色不修改
+fn set_cell_highlight(table_cell: &mut TableCell, highlight: &str) {
+    fn set_runs(children: &mut [ParagraphChild], highlight: &Option<String>) {
+        for item in children {
+            match item {
+                ParagraphChild::Run(run) => {
+                    let run_property = mem::take(&mut run.run_property);
+                    run.run_property = match highlight {
+                        Some(color) => run_property.highlight(color),
+                        None => RunProperty {
+                            highlight: None,
+                            ..run_property
+                        },
+                    };
+                }
+                ParagraphChild::Hyperlink(hyperlink) => {
+                    set_runs(&mut hyperlink.children, highlight)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let highlight = match highlight {
+        "" | "none" => None,
+        color => match HIGHLIGHT_COLORS
+            .iter()
+            .find(|item| item.eq_ignore_ascii_case(color))
         {
-            table_border = table_border.border_type(border_type);
+            Some(color) => Some(color.to_string()),
+            None => return,
+        },
+    };
+    for item in &mut table_cell.children {
+        if let TableCellContent::Paragraph(paragraph) = item {
+            set_runs(&mut paragraph.children, &highlight);
         }
+    }
+}
 
-        return property.set_border(table_border);
+// cell文字的旋转角度，tbRl为顺时针90度，btLr为270度，其他按不旋转处理
+fn cell_text_rotation(table_cell: &TableCell) -> u32 {
+    let property_value =
+        serde_json::to_value(&table_cell.property).unwrap_or(serde_json::Value::Null);
+    match property_value
+        .get("textDirection")
+        .and_then(|item| item.as_str())
+    {
+        Some("tbRl") | Some("tbRlV") => 90,
+        Some("btLr") => 270,
+        _ => 0,
+    }
+}
+
+// 设置文字旋转角度，不支持的角度不修改
+// 旋转后垂直对齐决定文字的水平位置，段落对齐决定垂直位置，两者都设为居中文字才会居中
+fn set_cell_text_rotation(table_cell: &mut TableCell, rotation: u32) {
+    let text_direction = match rotation {
+        0 => TextDirectionType::Lr,
+        90 => TextDirectionType::TbRl,
+        270 => TextDirectionType::BtLr,
+        _ => return,
+    };
+    let property = mem::take(&mut table_cell.property);
+    table_cell.property = property.text_direction(text_direction);
+    if rotation != 0 {
+        let property = mem::take(&mut table_cell.property);
+        table_cell.property = property.vertical_align(VAlignType::Center);
+        set_cell_justification(table_cell, "center");
+    }
+}
+
+// 段落子元素的文本，包含超链接和修订插入的文本，cell和paragraph表共用
+pub(crate) fn paragraph_children_text(children: &[ParagraphChild]) -> String {
+    children
+        .iter()
+        .map(|item| match item {
+            ParagraphChild::Run(run) => run_text(run),
+            ParagraphChild::Hyperlink(hyperlink) => paragraph_children_text(&hyperlink.children),
+            ParagraphChild::Insert(insert) => insert
+                .children
+                .iter()
+                .map(|item| match item {
+                    InsertChild::Run(run) => run_text(run),
+                    _ => "".to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(""),
+            _ => "".to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+// run的文本，制表符转为"\t"，换行转为"\n"
+fn run_text(run: &Run) -> String {
+    run.children
+        .iter()
+        .map(|item| match item {
+            RunChild::Text(run_text) => unescape_text(&run_text.text),
+            RunChild::Tab(_) => "\t".to_string(),
+            RunChild::Break(_) => "\n".to_string(),
+            _ => "".to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+// Text中保存的是转义后的xml文本（Run::add_text会转义），还原为原始文本
+fn unescape_text(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#xA;", "\n")
+        .replace("&amp;", "&")
+}
+
+// 段落子元素中的超链接地址
+// 从文件读取的外部链接只有rid，没有地址时使用rid；文档内锚点以"#"开头
+fn paragraph_children_links(children: &[ParagraphChild], links: &mut Vec<String>) {
+    for item in children {
+        if let ParagraphChild::Hyperlink(hyperlink) = item {
+            links.push(match &hyperlink.link {
+                HyperlinkData::External { rid, path } => {
+                    if path.is_empty() {
+                        rid.clone()
+                    } else {
+                        path.clone()
+                    }
+                }
+                HyperlinkData::Anchor { anchor } => format!("#{anchor}"),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_parser::DocxDb;
+    use crate::sql_parser::test_util::{execute, scan, table_docx, text_cell};
+    use docx_rs::{Hyperlink, HyperlinkType, Run, TableRow};
+    use futures::StreamExt;
+    use futures::executor::block_on;
+    use gluesql::prelude::Glue;
+
+    // 收集scan_data的所有行
+    fn scan_rows(docx: &Document) -> Vec<HashMap<String, Value>> {
+        futures::executor::block_on(async {
+            let rows = Cell.scan_data(docx, false).await.unwrap();
+            rows.map(|item| match item.unwrap().1 {
+                DataRow::Map(hm) => hm,
+                _ => HashMap::new(),
+            })
+            .collect::<Vec<_>>()
+            .await
+        })
+    }
+
+    #[test]
+    fn for_each_cell_edits_in_scan_order() {
+        let mut docx = table_docx(&[&["a", "b"], &["c", "d"]])
+            .add_table(Table::new(vec![TableRow::new(vec![text_cell("e")])]));
+        let mut positions = vec![];
+        for_each_cell(&mut docx.document, |mut ctx| {
+            positions.push((ctx.table_index, ctx.row_index, ctx.column_index));
+            let text = ctx.text().to_uppercase();
+            ctx.set_text(&text);
+            if ctx.row_index == 0 {
+                ctx.set_border(TableCellBorderPosition::Bottom, r#"{"size":12}"#);
+            }
+        });
+        assert_eq!(
+            positions,
+            vec![(0, 0, 0), (0, 0, 1), (0, 1, 0), (0, 1, 1), (1, 0, 0)]
+        );
+
+        let rows = scan_rows(&docx.document);
+        let contents = rows
+            .iter()
+            .map(|row| row["content"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contents,
+            ["A", "B", "C", "D", "E"].map(|text| Value::Str(text.to_string()))
+        );
+        assert!(
+            matches!(&rows[0]["borders_bottom"], Value::Str(border) if border.contains("\"size\":12"))
+        );
+        assert_eq!(rows[2]["borders_bottom"], Value::Null);
+    }
+
+    #[test]
+    fn update_width_with_pct_scaling() {
+        let mut docx = table_docx(&[&["a"]]);
+        execute(&mut docx, "update cell set width = 2000");
+        let row = &scan(&mut docx, "cell")[0];
+        assert_eq!(row["width"], Value::U32(2000));
+        assert_eq!(row["width_type"], Value::Str("dxa".to_string()));
+
+        // 0~100按百分比换算
+        execute(&mut docx, "update cell set width = 50, width_type = 'pct'");
+        assert_eq!(scan(&mut docx, "cell")[0]["width"], Value::U32(2500));
+        // 与读取的值相同时不再换算
+        execute(&mut docx, "update cell set width_type = width_type");
+        assert_eq!(scan(&mut docx, "cell")[0]["width"], Value::U32(2500));
+
+        let mut glue = Glue::new(DocxDb::new(&mut docx));
+        let error = block_on(glue.execute("update cell set width = 6000")).unwrap_err();
+        assert!(error.to_string().contains("超出范围"));
+        let error = block_on(glue.execute("update cell set width_type = 'cm'")).unwrap_err();
+        assert!(error.to_string().contains("不支持的宽度类型"));
+    }
+
+    #[test]
+    fn update_columns_together_is_atomic() {
+        let mut docx = table_docx(&[&["a"]]);
+        execute(
+            &mut docx,
+            r#"update cell set width_type = 'pct', borders_top = '{"size":8}', width = 20, content = 'b', borders_left = '{"size":4}'"#,
+        );
+        let row = &scan(&mut docx, "cell")[0];
+        assert_eq!(row["width"], Value::U32(1000));
+        assert_eq!(row["width_type"], Value::Str("pct".to_string()));
+        assert_eq!(row["content"], Value::Str("b".to_string()));
+        assert_ne!(row["borders_top"], Value::Null);
+        assert_ne!(row["borders_left"], Value::Null);
+
+        // 宽度校验失败时其他列也不修改
+        let mut glue = Glue::new(DocxDb::new(&mut docx));
+        assert!(
+            block_on(glue.execute("update cell set content = 'c', width_type = 'cm'")).is_err()
+        );
+        assert_eq!(
+            scan(&mut docx, "cell")[0]["content"],
+            Value::Str("b".to_string())
+        );
+    }
+
+    #[test]
+    fn trim_content_on_write() {
+        let mut docx = table_docx(&[&["a", "b"]]);
+        let mut glue = Glue::new(DocxDb::new(&mut docx).trim_content(true));
+        block_on(glue.execute(
+            "update cell set content = '  张三 \t 李四 \n  第二行   ' where content = 'a'",
+        ))
+        .unwrap();
+        drop(glue);
+        let rows = scan(&mut docx, "cell");
+        assert_eq!(
+            rows[0]["content"],
+            Value::Str("张三 李四\n第二行".to_string())
+        );
+
+        // 默认不处理
+        execute(
+            &mut docx,
+            "update cell set content = ' c ' where content = 'b'",
+        );
+        assert_eq!(
+            scan(&mut docx, "cell")[1]["content"],
+            Value::Str(" c ".to_string())
+        );
+    }
+
+    #[test]
+    fn dry_run_plans_without_applying() {
+        let mut docx = table_docx(&[&["a", "b"]]);
+        let planned = {
+            let mut glue = Glue::new(DocxDb::new(&mut docx).dry_run(true));
+            block_on(glue.execute("update cell set content = 'x' where content = 'a'")).unwrap();
+            // 值不变的行不计入
+            block_on(glue.execute("update cell set content = content")).unwrap();
+            glue.storage.planned
+        };
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].table_name, "cell");
+        assert_eq!(
+            planned[0].columns,
+            vec![(
+                "content".to_string(),
+                Value::Str("a".to_string()),
+                Value::Str("x".to_string())
+            )]
+        );
+
+        // 文档未修改
+        let rows = scan(&mut docx, "cell");
+        assert_eq!(rows[0]["content"], Value::Str("a".to_string()));
+        assert_eq!(Key::Str(String::from(&rows[0]["hash"])), planned[0].key);
+    }
+
+    #[test]
+    fn scan_cell_with_hyperlink() {
+        let paragraph = Paragraph::new()
+            .add_run(Run::new().add_text("详见"))
+            .add_hyperlink(
+                Hyperlink::new("https://example.com", HyperlinkType::External)
+                    .add_run(Run::new().add_text("官网")),
+            );
+        let docx = Docx::new().add_table(Table::new(vec![TableRow::new(vec![
+            TableCell::new().add_paragraph(paragraph),
+        ])]));
+
+        let rows = scan_rows(&docx.document);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["content"], Value::Str("详见官网".to_string()));
+        assert_eq!(
+            rows[0]["links"],
+            Value::Str("[\"https://example.com\"]".to_string())
+        );
+    }
+
+    #[test]
+    fn justification_round_trip_is_idempotent() {
+        let cell = TableCell::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("a")))
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("b"))
+                    .align(AlignmentType::Center),
+            );
+        let centered = TableCell::new().add_paragraph(
+            Paragraph::new()
+                .add_run(Run::new().add_text("c"))
+                .align(AlignmentType::Right),
+        );
+        let mut docx = Docx::new().add_table(Table::new(vec![TableRow::new(vec![cell, centered])]));
+        // 所有cell中的段落
+        let paragraphs = |document: &Document| {
+            let mut paragraphs = vec![];
+            for child in &document.children {
+                if let DocumentChild::Table(table) = child {
+                    for TableChild::TableRow(row) in &table.rows {
+                        for TableRowChild::TableCell(cell) in &row.cells {
+                            paragraphs.push(serde_json::to_string(&cell.children).unwrap());
+                        }
+                    }
+                }
+            }
+            paragraphs
+        };
+        let before = paragraphs(&docx.document);
+
+        // 读取后原样写回，段落不变
+        let rows = scan_rows(&docx.document);
+        assert_eq!(rows[0]["justification"], Value::Str("".to_string()));
+        assert_eq!(rows[1]["justification"], Value::Str("right".to_string()));
+        let keyed_rows = rows
+            .into_iter()
+            .map(|hm| match &hm["hash"] {
+                Value::Str(hash) => (Key::Str(hash.clone()), DataRow::Map(hm.clone())),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        futures::executor::block_on(Cell.insert_data(&mut docx.document, keyed_rows, false))
+            .unwrap();
+        assert_eq!(paragraphs(&docx.document), before);
+    }
+    #[test]
+    fn set_border_keeps_unspecified_attributes() {
+        let property = TableCellProperty::new().set_border(
+            TableCellBorder::new(TableCellBorderPosition::Top)
+                .size(12)
+                .border_type(BorderType::Double),
+        );
+
+        let property = Cell.set_border(
+            property,
+            &"{\"color\":\"ff0000\"}".to_string(),
+            TableCellBorderPosition::Top,
+        );
+        let top = serde_json::to_value(&property).unwrap()["borders"]["top"].clone();
+        assert_eq!(top["color"], "ff0000");
+        assert_eq!(top["size"], 12);
+        assert_eq!(top["borderType"], "double");
+    }
+
+    #[test]
+    fn update_border_through_sql_keeps_size() {
+        let mut docx = table_docx(&[&["a"]]);
+        execute(
+            &mut docx,
+            "update cell set borders_top = '{\"size\":12,\"borderType\":\"double\"}'",
+        );
+        execute(
+            &mut docx,
+            "update cell set borders_top = '{\"color\":\"ff0000\"}'",
+        );
+
+        let rows = scan(&mut docx, "cell");
+        let top: serde_json::Value = match &rows[0]["borders_top"] {
+            Value::Str(top) => serde_json::from_str(top).unwrap(),
+            _ => unreachable!(),
+        };
+        assert_eq!(top["color"], "ff0000");
+        assert_eq!(top["size"], 12);
+        assert_eq!(top["borderType"], "double");
+    }
+
+    #[test]
+    fn fetch_data_matches_scanned_row() {
+        let docx = table_docx(&[&["a", "b"], &["c", "d"]]);
+        let rows = scan_rows(&docx.document);
+        let key = match &rows[3]["hash"] {
+            Value::Str(hash) => Key::Str(hash.clone()),
+            _ => unreachable!(),
+        };
+
+        let row =
+            futures::executor::block_on(Cell.fetch_data(&docx.document, &key, false)).unwrap();
+        assert_eq!(row, Some(DataRow::Map(rows[3].clone())));
+    }
+
+    #[test]
+    fn content_includes_nested_table() {
+        let nested = Table::new(vec![
+            TableRow::new(vec![text_cell("a"), text_cell("b")]),
+            TableRow::new(vec![text_cell("c"), text_cell("d")]),
+        ]);
+        let docx = Docx::new().add_table(Table::new(vec![TableRow::new(vec![
+            text_cell("外层").add_table(nested),
+        ])]));
+
+        let rows = scan_rows(&docx.document);
+        assert_eq!(
+            rows[0]["content"],
+            Value::Str("外层\na\tb\nc\td".to_string())
+        );
+    }
+
+    #[test]
+    fn match_cell_by_normalized_content() {
+        let mut docx = table_docx(&[&["  总计 \u{3000}金额 ", "总计 金额"]]);
+        let rows = scan(&mut docx, "cell");
+        assert_eq!(
+            rows[0]["content"],
+            Value::Str("  总计 \u{3000}金额 ".to_string())
+        );
+        assert_eq!(
+            rows[0]["content_normalized"],
+            Value::Str("总计 金额".to_string())
+        );
+
+        execute(
+            &mut docx,
+            "update cell set justification = 'center' where content_normalized = '总计 金额'",
+        );
+        let rows = scan(&mut docx, "cell");
+        assert!(
+            rows.iter()
+                .all(|row| row["justification"] == Value::Str("center".to_string()))
+        );
+    }
+
+    #[test]
+    fn rotate_text_centers_cell() {
+        let mut docx = table_docx(&[&["竖排表头", "b"]]);
+        execute(
+            &mut docx,
+            "update cell set text_rotation = 270 where content = '竖排表头'",
+        );
+
+        let rows = scan(&mut docx, "cell");
+        assert_eq!(rows[0]["text_rotation"], Value::U32(270));
+        assert_eq!(rows[0]["justification"], Value::Str("center".to_string()));
+        assert_eq!(rows[1]["text_rotation"], Value::U32(0));
+        let table_cell = table_cells(tables(&docx.document).next().unwrap())
+            .next()
+            .unwrap();
+        let property = serde_json::to_value(&table_cell.property).unwrap();
+        assert_eq!(property["textDirection"], "btLr");
+        assert_eq!(property["verticalAlign"], "center");
+
+        // 不支持的角度不修改
+        execute(&mut docx, "update cell set text_rotation = 45");
+        assert_eq!(scan(&mut docx, "cell")[0]["text_rotation"], Value::U32(270));
+    }
+
+    #[test]
+    fn update_content_from_expression_persists() {
+        let mut docx = table_docx(&[&["a", "b"], &["c", "d"]]);
+
+        // 字符串拼接和子查询的结果都走content的写入逻辑
+        execute(
+            &mut docx,
+            "update cell set content = 'prefix-' || content where content = 'a'",
+        );
+        execute(
+            &mut docx,
+            "update cell set content = (select content from cell where content = 'd') where content = 'b'",
+        );
+        execute(
+            &mut docx,
+            "update cell set content = 'x & <y>' where content = 'c'",
+        );
+        assert_eq!(
+            scan(&mut docx, "cell")[2]["content"],
+            Value::Str("x & <y>".to_string())
+        );
+
+        // 保存后重新读取，内容不丢失
+        let mut buf = std::io::Cursor::new(vec![]);
+        docx.build().pack(&mut buf).unwrap();
+        let mut docx = read_docx(&buf.into_inner()).unwrap();
+        let contents = scan(&mut docx, "cell")
+            .into_iter()
+            .map(|row| row["content"].clone())
+            .collect::<Vec<Value>>();
+        assert_eq!(
+            contents,
+            ["prefix-a", "d", "x & <y>", "d"]
+                .iter()
+                .map(|item| Value::Str(item.to_string()))
+                .collect::<Vec<Value>>()
+        );
+    }
+
+    #[test]
+    fn update_content_keeps_paragraph_structure() {
+        use docx_rs::Docx;
+
+        let cell = text_cell("第一行")
+            .add_paragraph(
+                Paragraph::new()
+                    .align(AlignmentType::Right)
+                    .add_run(Run::new().add_text("第二行").bold()),
+            )
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("第三行")));
+        let mut docx = Docx::new().add_table(Table::new(vec![TableRow::new(vec![cell])]));
+        let paragraphs = |docx: &Docx| {
+            let table_cell = table_cells(tables(&docx.document).next().unwrap())
+                .next()
+                .unwrap();
+            table_cell
+                .children
+                .iter()
+                .filter_map(|item| match item {
+                    TableCellContent::Paragraph(paragraph) => Some(paragraph.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<Paragraph>>()
+        };
+        assert_eq!(
+            scan(&mut docx, "cell")[0]["content"],
+            Value::Str("第一行\n第二行\n第三行".to_string())
+        );
+
+        // 行数减少时删除多余的段落，沿用原段落的属性
+        execute(&mut docx, "update cell set content = 'a\nb'");
+        let result = paragraphs(&docx);
+        assert_eq!(result.len(), 2);
+        assert_eq!(paragraph_children_text(&result[1].children), "b");
+        assert!(
+            serde_json::to_string(&result[1].property)
+                .unwrap()
+                .contains("right")
+        );
+        assert!(
+            serde_json::to_string(&result[1].children)
+                .unwrap()
+                .contains("\"bold\":true")
+        );
+
+        // 行数增加时沿用最后一个段落的属性
+        execute(&mut docx, "update cell set content = 'a\nb\nc\nd'");
+        let result = paragraphs(&docx);
+        assert_eq!(result.len(), 4);
+        assert_eq!(paragraph_children_text(&result[3].children), "d");
+        assert_eq!(result[3].property.alignment, result[1].property.alignment);
+    }
+
+    #[test]
+    fn update_highlight_marks_all_runs() {
+        let mut docx = table_docx(&[&["重点", "普通"]]);
+        assert_eq!(
+            scan(&mut docx, "cell")[0]["highlight"],
+            Value::Str("".to_string())
+        );
+
+        execute(
+            &mut docx,
+            "update cell set highlight = 'Yellow' where content = '重点'",
+        );
+        let rows = scan(&mut docx, "cell");
+        assert_eq!(rows[0]["highlight"], Value::Str("yellow".to_string()));
+        assert_eq!(rows[1]["highlight"], Value::Str("".to_string()));
+
+        // 不支持的颜色不修改，空字符串移除
+        execute(&mut docx, "update cell set highlight = 'orange'");
+        assert_eq!(
+            scan(&mut docx, "cell")[0]["highlight"],
+            Value::Str("yellow".to_string())
+        );
+        execute(&mut docx, "update cell set highlight = ''");
+        assert_eq!(
+            scan(&mut docx, "cell")[0]["highlight"],
+            Value::Str("".to_string())
+        );
     }
 }
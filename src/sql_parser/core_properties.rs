@@ -0,0 +1,229 @@
+use std::io::Read;
+
+use docx_rs::Docx;
+use futures::stream;
+use gluesql::{
+    core::{
+        ast::ColumnDef,
+        data::{Schema, Value},
+        store::{DataRow, RowIter},
+    },
+    prelude::{DataType, Key, Result},
+};
+use regex::Regex;
+
+// docProps/core.xml中的文档属性
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoreProperties {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+}
+
+impl CoreProperties {
+    // docx-rs读取时不解析core.xml，只有代码中设置的属性
+    pub fn from_docx(docx: &Docx) -> Self {
+        let value = serde_json::to_value(&docx.doc_props.core).unwrap_or(serde_json::Value::Null);
+        let field = |name: &str| value["config"][name].as_str().map(|item| item.to_string());
+        CoreProperties {
+            title: field("title"),
+            author: field("creator"),
+            created: field("created"),
+            modified: field("modified"),
+        }
+    }
+
+    // 从docx文件中读取，没有core.xml时各属性为空
+    pub fn from_docx_bytes(docx_bytes: &[u8]) -> Self {
+        let mut core_xml = String::new();
+        let read = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes))
+            .ok()
+            .and_then(|mut archive| {
+                archive
+                    .by_name("docProps/core.xml")
+                    .ok()?
+                    .read_to_string(&mut core_xml)
+                    .ok()
+            });
+        if read.is_none() {
+            return CoreProperties::default();
+        }
+        CoreProperties {
+            title: xml_element_text(&core_xml, "dc:title"),
+            author: xml_element_text(&core_xml, "dc:creator"),
+            created: xml_element_text(&core_xml, "dcterms:created"),
+            modified: xml_element_text(&core_xml, "dcterms:modified"),
+        }
+    }
+}
+
+// 元素的文本，元素不存在或为空时返回None
+fn xml_element_text(xml: &str, tag: &str) -> Option<String> {
+    let regex = Regex::new(&format!(r"<{tag}(?:\s[^>]*)?>([^<]*)</{tag}>")).ok()?;
+    let text = regex.captures(xml)?.get(1)?.as_str();
+    if text.is_empty() {
+        return None;
+    }
+    Some(
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+// 文档属性的单行表，只读
+pub struct CorePropertiesTable;
+
+impl CorePropertiesTable {
+    pub fn table_name(&self) -> String {
+        "core_properties".to_string()
+    }
+
+    pub fn fetch_all_schemas(&self) -> Vec<Schema> {
+        vec![Schema {
+            table_name: "core_properties".to_string(),
+            column_defs: Some(vec![
+                ColumnDef {
+                    name: "title".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("标题，dc:title".to_string()),
+                },
+                ColumnDef {
+                    name: "author".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("作者，dc:creator".to_string()),
+                },
+                ColumnDef {
+                    name: "created".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("创建时间，dcterms:created".to_string()),
+                },
+                ColumnDef {
+                    name: "modified".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("修改时间，dcterms:modified".to_string()),
+                },
+            ]),
+            indexes: vec![],
+            engine: None,
+            foreign_keys: vec![],
+            comment: None,
+        }]
+    }
+
+    pub async fn fetch_data(
+        &self,
+        properties: &CoreProperties,
+        key: &Key,
+    ) -> Result<Option<DataRow>> {
+        if *key == Self::key() {
+            return Ok(Some(self.data_row(properties)));
+        }
+
+        Ok(None)
+    }
+
+    pub async fn scan_data<'a>(&self, properties: &CoreProperties) -> Result<RowIter<'a>> {
+        let rows = vec![Ok((Self::key(), self.data_row(properties)))];
+        Ok(Box::pin(stream::iter(rows)))
+    }
+
+    fn key() -> Key {
+        Key::Str("core_properties".to_string())
+    }
+
+    // 按列的顺序返回，select *时列名和值一一对应
+    fn data_row(&self, properties: &CoreProperties) -> DataRow {
+        let text = |value: &Option<String>| match value {
+            Some(value) => Value::Str(value.clone()),
+            None => Value::Null,
+        };
+        DataRow::Vec(vec![
+            text(&properties.title),
+            text(&properties.author),
+            text(&properties.created),
+            text(&properties.modified),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_parser::DocxDb;
+    use futures::executor::block_on;
+    use gluesql::prelude::{Glue, Payload};
+    use std::io::Write;
+
+    #[test]
+    fn select_core_properties() {
+        let mut buf = std::io::Cursor::new(vec![]);
+        let mut writer = zip::ZipWriter::new(&mut buf);
+        writer
+            .start_file("docProps/core.xml", zip::write::FileOptions::default())
+            .unwrap();
+        writer
+            .write_all(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>合同 &amp; 附件</dc:title><dc:creator>张三</dc:creator><dc:description></dc:description><dcterms:created xsi:type="dcterms:W3CDTF">2024-01-02T03:04:05Z</dcterms:created></cp:coreProperties>"#
+                    .as_bytes(),
+            )
+            .unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let mut docx = Docx::new();
+        let db =
+            DocxDb::new(&mut docx).core_properties(CoreProperties::from_docx_bytes(buf.get_ref()));
+        let mut glue = Glue::new(db);
+        let payloads = block_on(glue.execute("select * from core_properties")).unwrap();
+        let Payload::Select { rows, .. } = &payloads[0] else {
+            panic!("应为select结果");
+        };
+        assert_eq!(
+            rows,
+            &vec![vec![
+                Value::Str("合同 & 附件".to_string()),
+                Value::Str("张三".to_string()),
+                Value::Str("2024-01-02T03:04:05Z".to_string()),
+                Value::Null,
+            ]]
+        );
+
+        // 没有指定时使用代码中设置的属性
+        let mut docx = Docx::new();
+        docx.doc_props.core = docx
+            .doc_props
+            .core
+            .clone()
+            .created_at("2024-05-06T00:00:00Z");
+        let mut glue = Glue::new(DocxDb::new(&mut docx));
+        let payloads =
+            block_on(glue.execute("select title, created from core_properties")).unwrap();
+        let Payload::Select { rows, .. } = &payloads[0] else {
+            panic!("应为select结果");
+        };
+        assert_eq!(
+            rows,
+            &vec![vec![
+                Value::Null,
+                Value::Str("2024-05-06T00:00:00Z".to_string())
+            ]]
+        );
+    }
+}
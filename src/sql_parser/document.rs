@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use docx_rs::{Docx, PageOrientationType, PageSize, RunFonts, SectionProperty};
+use futures::stream;
+use gluesql::{
+    core::{
+        ast::ColumnDef,
+        data::{Schema, Value},
+        store::{DataRow, RowIter},
+    },
+    prelude::{DataType, Key, Result},
+};
+use std::mem;
+use std::str::FromStr;
+
+// 文档级别的单行表，对应styles中的docDefaults
+pub struct DocumentInfo;
+
+impl DocumentInfo {
+    pub fn table_name(&self) -> String {
+        "document".to_string()
+    }
+
+    pub fn fetch_all_schemas(&self) -> Vec<Schema> {
+        vec![Schema {
+            table_name: "document".to_string(),
+            column_defs: Some(vec![
+                ColumnDef {
+                    name: "default_font".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("默认字体".to_string()),
+                },
+                ColumnDef {
+                    name: "default_font_size".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("默认字号，单位半磅".to_string()),
+                },
+                ColumnDef {
+                    name: "page_width".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("页面宽度，单位twip".to_string()),
+                },
+                ColumnDef {
+                    name: "page_height".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("页面高度，单位twip".to_string()),
+                },
+                ColumnDef {
+                    name: "orientation".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("纸张方向，portrait或landscape".to_string()),
+                },
+            ]),
+            indexes: vec![],
+            engine: None,
+            foreign_keys: vec![],
+            comment: None,
+        }]
+    }
+
+    pub async fn fetch_data(&self, docx: &Docx, key: &Key) -> Result<Option<DataRow>> {
+        if *key == Self::key() {
+            return Ok(Some(self.data_row(docx)));
+        }
+
+        Ok(None)
+    }
+
+    pub async fn scan_data<'a>(&self, docx: &Docx) -> Result<RowIter<'a>> {
+        let rows = vec![Ok((Self::key(), self.data_row(docx)))];
+        Ok(Box::pin(stream::iter(rows)))
+    }
+
+    pub async fn insert_data(&self, docx: &mut Docx, rows: Vec<(Key, DataRow)>) -> Result<()> {
+        for row in &rows {
+            if row.0 == Self::key()
+                && let DataRow::Map(kvs) = &row.1
+            {
+                // update时传入整行，只修改与当前值不同的列
+                let DataRow::Map(current) = self.data_row(docx) else {
+                    continue;
+                };
+                let changed = |column: &str| kvs.get(column) != current.get(column);
+                if changed("default_font")
+                    && let Some(Value::Str(font)) = kvs.get("default_font")
+                {
+                    let fonts = RunFonts::new()
+                        .ascii(font)
+                        .hi_ansi(font)
+                        .east_asia(font)
+                        .cs(font);
+                    let doc_defaults = mem::take(&mut docx.styles.doc_defaults);
+                    docx.styles.doc_defaults = doc_defaults.fonts(fonts);
+                }
+                if changed("default_font_size")
+                    && let Some(Value::U32(size)) = kvs.get("default_font_size")
+                {
+                    let doc_defaults = mem::take(&mut docx.styles.doc_defaults);
+                    docx.styles.doc_defaults = doc_defaults.size(*size as usize);
+                }
+                if ["page_width", "page_height", "orientation"]
+                    .iter()
+                    .any(|column| changed(column))
+                {
+                    Self::set_page_size(docx, kvs);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // 设置节的页面大小和方向，修改方向时交换宽高，只修改宽高时按宽高确定方向
+    fn set_page_size(docx: &mut Docx, kvs: &HashMap<String, Value>) {
+        let (mut width, mut height, current) = page_size(&docx.document.section_property);
+        if let Some(Value::U32(w)) = kvs.get("page_width") {
+            width = *w;
+        }
+        if let Some(Value::U32(h)) = kvs.get("page_height") {
+            height = *h;
+        }
+
+        let landscape = match kvs.get("orientation") {
+            Some(Value::Str(o)) if *o != current => {
+                let landscape = PageOrientationType::from_str(o)
+                    .is_ok_and(|item| item == PageOrientationType::Landscape);
+                if landscape == (width < height) {
+                    mem::swap(&mut width, &mut height);
+                }
+                landscape
+            }
+            _ => width > height,
+        };
+        let orient = if landscape {
+            PageOrientationType::Landscape
+        } else {
+            PageOrientationType::Portrait
+        };
+        docx.document.section_property.page_size =
+            PageSize::new().size(width, height).orient(orient);
+    }
+
+    fn key() -> Key {
+        Key::Str("document".to_string())
+    }
+
+    fn data_row(&self, docx: &Docx) -> DataRow {
+        // 使用json读取默认的run属性
+        let run_property = serde_json::to_value(&docx.styles.doc_defaults)
+            .unwrap_or(serde_json::Value::Null)
+            .get("runPropertyDefault")
+            .and_then(|item| item.get("runProperty"))
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+
+        let fonts = run_property.get("fonts");
+        let default_font = ["eastAsia", "ascii", "hiAnsi", "cs"]
+            .iter()
+            .find_map(|name| fonts.and_then(|item| item.get(name)))
+            .and_then(|item| item.as_str())
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null);
+        let default_font_size = run_property
+            .get("sz")
+            .and_then(|item| item.as_u64())
+            .map(|item| Value::U32(item as u32))
+            .unwrap_or(Value::Null);
+
+        let mut hm: HashMap<String, Value> = HashMap::new();
+        hm.insert("default_font".to_string(), default_font);
+        hm.insert("default_font_size".to_string(), default_font_size);
+        let (page_width, page_height, orientation) = page_size(&docx.document.section_property);
+        hm.insert("page_width".to_string(), Value::U32(page_width));
+        hm.insert("page_height".to_string(), Value::U32(page_height));
+        hm.insert("orientation".to_string(), Value::Str(orientation));
+        DataRow::Map(hm)
+    }
+}
+
+// 读取节的页面宽度、高度和方向，未指定方向时按宽高判断
+pub(crate) fn page_size(section_property: &SectionProperty) -> (u32, u32, String) {
+    let page_size =
+        serde_json::to_value(&section_property.page_size).unwrap_or(serde_json::Value::Null);
+    let width = page_size
+        .get("w")
+        .and_then(|item| item.as_u64())
+        .unwrap_or(0) as u32;
+    let height = page_size
+        .get("h")
+        .and_then(|item| item.as_u64())
+        .unwrap_or(0) as u32;
+    let orientation = page_size
+        .get("orient")
+        .and_then(|item| item.as_str())
+        .map(|item| item.to_string())
+        .unwrap_or(
+            if width > height {
+                "landscape"
+            } else {
+                "portrait"
+            }
+            .to_string(),
+        );
+    (width, height, orientation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_parser::DocxDb;
+    use crate::sql_parser::test_util::{execute, scan};
+    use docx_rs::BuildXML;
+    use futures::executor::block_on;
+    use gluesql::prelude::{Glue, Payload};
+
+    fn select_page(glue: &mut Glue<DocxDb>) -> Vec<Value> {
+        let payloads =
+            block_on(glue.execute("select page_width, page_height, orientation from document"))
+                .unwrap();
+        match &payloads[0] {
+            Payload::Select { rows, .. } => rows[0].clone(),
+            _ => panic!("not select"),
+        }
+    }
+
+    #[test]
+    fn update_orientation_swaps_page_size() {
+        let mut docx = Docx::new();
+        let mut glue = Glue::new(DocxDb::new(&mut docx));
+        assert_eq!(
+            select_page(&mut glue),
+            vec![
+                Value::U32(11906),
+                Value::U32(16838),
+                Value::Str("portrait".to_string())
+            ]
+        );
+
+        block_on(glue.execute("update document set orientation = 'landscape'")).unwrap();
+        assert_eq!(
+            select_page(&mut glue),
+            vec![
+                Value::U32(16838),
+                Value::U32(11906),
+                Value::Str("landscape".to_string())
+            ]
+        );
+    }
+
+    // 默认字体四个位置的字体
+    fn default_fonts(docx: &Docx) -> serde_json::Value {
+        serde_json::to_value(&docx.styles.doc_defaults)
+            .unwrap_or(serde_json::Value::Null)
+            .pointer("/runPropertyDefault/runProperty/fonts")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null)
+    }
+
+    #[test]
+    fn update_default_font_and_size() {
+        let mut docx = Docx::new().default_fonts(
+            RunFonts::new()
+                .ascii("Arial")
+                .hi_ansi("Arial")
+                .cs("Arial")
+                .east_asia("SimSun"),
+        );
+        let fonts = default_fonts(&docx);
+
+        // 只修改字号时字体不变
+        execute(&mut docx, "update document set default_font_size = 24");
+        assert_eq!(default_fonts(&docx), fonts);
+        assert_eq!(
+            scan(&mut docx, "document")[0]["default_font_size"],
+            Value::U32(24)
+        );
+
+        // 修改字体时四个位置都使用新字体，字号不变
+        execute(&mut docx, "update document set default_font = '黑体'");
+        let fonts = default_fonts(&docx);
+        for name in ["ascii", "hiAnsi", "eastAsia", "cs"] {
+            assert_eq!(fonts[name], "黑体");
+        }
+        assert_eq!(
+            scan(&mut docx, "document")[0]["default_font_size"],
+            Value::U32(24)
+        );
+    }
+
+    #[test]
+    fn unrelated_update_keeps_page_size() {
+        let mut docx = Docx::new();
+        let page_size = |docx: &Docx| {
+            String::from_utf8(docx.document.section_property.page_size.build()).unwrap()
+        };
+        let before = page_size(&docx);
+        assert!(!before.contains("w:orient"));
+
+        execute(&mut docx, "update document set default_font_size = 24");
+        assert_eq!(page_size(&docx), before);
+    }
+}
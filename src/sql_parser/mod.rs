@@ -22,22 +22,114 @@ use sha2::{Digest, Sha256};
 use std::mem;
 
 pub mod cell;
+pub mod core_properties;
+pub mod document;
+pub mod paragraph;
+pub mod row;
+pub mod section;
 pub mod tables;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 pub struct DocxDb<'a> {
-    pub docx: &'a mut Document,
+    pub docx: &'a mut Docx,
     tables: tables::Tables,
     cell: cell::Cell,
+    row: row::Row,
+    document: document::DocumentInfo,
+    section: section::Section,
+    paragraph: paragraph::ParagraphTable,
+    core_properties_table: core_properties::CorePropertiesTable,
+    core_properties: core_properties::CoreProperties,
+    dry_run: bool,
+    trim_content: bool,
+    xml_content: bool,
+    // dry_run时记录的修改
+    pub planned: Vec<PlannedChange>,
+}
+
+// 一行计划中的修改，columns为(列名, 修改前, 修改后)，只包含值有变化的列
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedChange {
+    pub table_name: String,
+    pub key: Key,
+    pub columns: Vec<(String, Value, Value)>,
 }
 
 impl<'a> DocxDb<'a> {
-    pub fn new(docx: &mut Document) -> DocxDb {
+    pub fn new(docx: &mut Docx) -> DocxDb {
+        let core_properties = core_properties::CoreProperties::from_docx(docx);
         DocxDb {
             docx: docx,
             tables: tables::Tables,
             cell: cell::Cell,
+            row: row::Row,
+            document: document::DocumentInfo,
+            section: section::Section,
+            paragraph: paragraph::ParagraphTable,
+            core_properties_table: core_properties::CorePropertiesTable,
+            core_properties,
+            dry_run: false,
+            trim_content: false,
+            xml_content: false,
+            planned: vec![],
         }
     }
+
+    // 只记录修改，不改动文档
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    // 读取docx文件时传入从文件中解析的文档属性
+    pub fn core_properties(mut self, core_properties: core_properties::CoreProperties) -> Self {
+        self.core_properties = core_properties;
+        self
+    }
+
+    // 写入cell的content时去掉多余的空白
+    pub fn trim_content(mut self, trim_content: bool) -> Self {
+        self.trim_content = trim_content;
+        self
+    }
+
+    // tables和cell的xml_content列需要序列化整个元素，开启后才生成，否则为null
+    pub fn xml_content(mut self, xml_content: bool) -> Self {
+        self.xml_content = xml_content;
+        self
+    }
+
+    // 与当前行比较，记录有变化的列
+    async fn plan_changes(&mut self, table_name: &str, rows: Vec<(Key, DataRow)>) -> Result<()> {
+        for (key, row) in rows {
+            let DataRow::Map(after) = row else {
+                continue;
+            };
+            let before = match self.fetch_data(table_name, &key).await? {
+                Some(DataRow::Map(before)) => before,
+                _ => HashMap::new(),
+            };
+            let mut columns = after
+                .into_iter()
+                .filter_map(|(name, value)| {
+                    let old = before.get(&name).cloned().unwrap_or(Value::Null);
+                    (old != value).then_some((name, old, value))
+                })
+                .collect::<Vec<_>>();
+            if columns.is_empty() {
+                continue;
+            }
+            columns.sort_by(|a, b| a.0.cmp(&b.0));
+            self.planned.push(PlannedChange {
+                table_name: table_name.to_string(),
+                key,
+                columns,
+            });
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait(?Send)]
@@ -61,16 +153,45 @@ impl<'b> Store for DocxDb<'b> {
         let mut schemas: Vec<Schema> = Vec::new();
         schemas.extend(self.tables.fetch_all_schemas());
         schemas.extend(self.cell.fetch_all_schemas());
+        schemas.extend(self.row.fetch_all_schemas());
+        schemas.extend(self.document.fetch_all_schemas());
+        schemas.extend(self.section.fetch_all_schemas());
+        schemas.extend(self.paragraph.fetch_all_schemas());
+        schemas.extend(self.core_properties_table.fetch_all_schemas());
         Result::Ok(schemas)
     }
 
     async fn fetch_data(&self, table_name: &str, key: &Key) -> Result<Option<DataRow>> {
         // 查找
         if self.tables.table_name() == table_name {
-            return self.tables.fetch_data(self.docx, key).await;
+            return self
+                .tables
+                .fetch_data(&self.docx.document, key, self.xml_content)
+                .await;
         }
         if self.cell.table_name() == table_name {
-            return self.cell.fetch_data(self.docx, key).await;
+            return self
+                .cell
+                .fetch_data(&self.docx.document, key, self.xml_content)
+                .await;
+        }
+        if self.row.table_name() == table_name {
+            return self.row.fetch_data(&self.docx.document, key).await;
+        }
+        if self.document.table_name() == table_name {
+            return self.document.fetch_data(self.docx, key).await;
+        }
+        if self.section.table_name() == table_name {
+            return self.section.fetch_data(&self.docx.document, key).await;
+        }
+        if self.paragraph.table_name() == table_name {
+            return self.paragraph.fetch_data(&self.docx.document, key).await;
+        }
+        if self.core_properties_table.table_name() == table_name {
+            return self
+                .core_properties_table
+                .fetch_data(&self.core_properties, key)
+                .await;
         }
 
         return Result::Ok(None);
@@ -80,10 +201,34 @@ impl<'b> Store for DocxDb<'b> {
     async fn scan_data<'a>(&'a self, table_name: &str) -> Result<RowIter<'a>> {
         // 查找
         if self.tables.table_name() == table_name {
-            return self.tables.scan_data(self.docx).await;
+            return self
+                .tables
+                .scan_data(&self.docx.document, self.xml_content)
+                .await;
         }
         if self.cell.table_name() == table_name {
-            return self.cell.scan_data(self.docx).await;
+            return self
+                .cell
+                .scan_data(&self.docx.document, self.xml_content)
+                .await;
+        }
+        if self.row.table_name() == table_name {
+            return self.row.scan_data(&self.docx.document).await;
+        }
+        if self.document.table_name() == table_name {
+            return self.document.scan_data(self.docx).await;
+        }
+        if self.section.table_name() == table_name {
+            return self.section.scan_data(&self.docx.document).await;
+        }
+        if self.paragraph.table_name() == table_name {
+            return self.paragraph.scan_data(&self.docx.document).await;
+        }
+        if self.core_properties_table.table_name() == table_name {
+            return self
+                .core_properties_table
+                .scan_data(&self.core_properties)
+                .await;
         }
 
         return Ok(Box::pin(stream::iter(vec![])));
@@ -115,12 +260,46 @@ impl<'b> StoreMut for DocxDb<'b> {
     }
 
     async fn insert_data(&mut self, table_name: &str, _rows: Vec<(Key, DataRow)>) -> Result<()> {
+        if self.dry_run
+            && self.section.table_name() != table_name
+            && self.paragraph.table_name() != table_name
+            && self.core_properties_table.table_name() != table_name
+        {
+            return self.plan_changes(table_name, _rows).await;
+        }
         // 查找
         if self.tables.table_name() == table_name {
-            return self.tables.insert_data(self.docx, _rows).await;
+            return self
+                .tables
+                .insert_data(&mut self.docx.document, _rows)
+                .await;
         }
         if self.cell.table_name() == table_name {
-            return self.cell.insert_data(self.docx, _rows).await;
+            return self
+                .cell
+                .insert_data(&mut self.docx.document, _rows, self.trim_content)
+                .await;
+        }
+        if self.row.table_name() == table_name {
+            return self.row.insert_data(&mut self.docx.document, _rows).await;
+        }
+        if self.document.table_name() == table_name {
+            return self.document.insert_data(self.docx, _rows).await;
+        }
+        if self.section.table_name() == table_name {
+            let msg = "[Storage] section is read-only".to_owned();
+
+            return Err(Error::StorageMsg(msg));
+        }
+        if self.paragraph.table_name() == table_name {
+            let msg = "[Storage] paragraph is read-only".to_owned();
+
+            return Err(Error::StorageMsg(msg));
+        }
+        if self.core_properties_table.table_name() == table_name {
+            let msg = "[Storage] core_properties is read-only".to_owned();
+
+            return Err(Error::StorageMsg(msg));
         }
 
         Ok(())
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use docx_rs::{Document, DocumentChild, Paragraph};
+use futures::stream;
+use gluesql::{
+    core::{
+        ast::ColumnDef,
+        data::{Schema, Value},
+        store::{DataRow, RowIter},
+    },
+    prelude::{DataType, Key, Result},
+};
+
+use super::cell::paragraph_children_text;
+
+// 正文中表格以外的段落，只读，用于按文本查找
+pub struct ParagraphTable;
+
+impl ParagraphTable {
+    pub fn table_name(&self) -> String {
+        "paragraph".to_string()
+    }
+
+    pub fn fetch_all_schemas(&self) -> Vec<Schema> {
+        vec![Schema {
+            table_name: "paragraph".to_string(),
+            column_defs: Some(vec![
+                ColumnDef {
+                    name: "index".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("段落的序号，从0开始，不包括表格中的段落".to_string()),
+                },
+                ColumnDef {
+                    name: "text".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("段落文本，与cell的content使用相同的提取规则".to_string()),
+                },
+                ColumnDef {
+                    name: "style".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("段落样式id，如Heading1".to_string()),
+                },
+            ]),
+            indexes: vec![],
+            engine: None,
+            foreign_keys: vec![],
+            comment: None,
+        }]
+    }
+
+    pub async fn fetch_data(&self, docx: &Document, key: &Key) -> Result<Option<DataRow>> {
+        // key是序号，直接定位，只生成这一行
+        let Key::U32(index) = key else {
+            return Ok(None);
+        };
+        let index = *index as usize;
+        Ok(paragraphs(docx)
+            .nth(index)
+            .map(|paragraph| paragraph_row(index, paragraph).1))
+    }
+
+    pub async fn scan_data<'a>(&self, docx: &Document) -> Result<RowIter<'a>> {
+        let rows = paragraphs(docx)
+            .enumerate()
+            .map(|(index, paragraph)| Ok(paragraph_row(index, paragraph)))
+            .collect::<Vec<_>>();
+        Ok(Box::pin(stream::iter(rows)))
+    }
+}
+
+// 正文中的所有段落
+fn paragraphs(docx: &Document) -> impl Iterator<Item = &Paragraph> {
+    docx.children
+        .iter()
+        .filter_map(|doc_child| match doc_child {
+            DocumentChild::Paragraph(paragraph) => Some(paragraph.as_ref()),
+            _ => None,
+        })
+}
+
+fn paragraph_row(index: usize, paragraph: &Paragraph) -> (Key, DataRow) {
+    let mut hm: HashMap<String, Value> = HashMap::new();
+    hm.insert("index".to_string(), Value::U32(index as u32));
+    hm.insert(
+        "text".to_string(),
+        Value::Str(paragraph_children_text(&paragraph.children)),
+    );
+    hm.insert(
+        "style".to_string(),
+        match &paragraph.property.style {
+            Some(style) => Value::Str(style.val.clone()),
+            None => Value::Null,
+        },
+    );
+    (Key::U32(index as u32), DataRow::Map(hm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_parser::{
+        DocxDb,
+        test_util::{scan, table_docx},
+    };
+    use docx_rs::{BreakType, Hyperlink, HyperlinkType, Insert, Run};
+    use futures::executor::block_on;
+    use gluesql::prelude::{Glue, Payload};
+
+    #[test]
+    fn select_paragraph_text_like() {
+        let mut docx = table_docx(&[&["表格中的合同"]])
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("本"))
+                    .add_hyperlink(
+                        Hyperlink::new("https://example.com", HyperlinkType::External)
+                            .add_run(Run::new().add_text("合")),
+                    )
+                    .add_insert(Insert::new(Run::new().add_text("同")))
+                    .add_run(Run::new().add_tab().add_text("第一条"))
+                    .style("Heading1"),
+            )
+            .add_paragraph(
+                Paragraph::new().add_run(
+                    Run::new()
+                        .add_text("第二条")
+                        .add_break(BreakType::TextWrapping)
+                        .add_text("其他"),
+                ),
+            );
+
+        let mut glue = Glue::new(DocxDb::new(&mut docx));
+        let payloads =
+            block_on(glue.execute("select text, style from paragraph where text like '%合同%'"))
+                .unwrap();
+        let Payload::Select { rows, .. } = &payloads[0] else {
+            panic!("应为select结果");
+        };
+        assert_eq!(
+            rows,
+            &vec![vec![
+                Value::Str("本合同\t第一条".to_string()),
+                Value::Str("Heading1".to_string()),
+            ]]
+        );
+
+        let rows = scan(&mut docx, "paragraph");
+        assert_eq!(rows[1]["text"], Value::Str("第二条\n其他".to_string()));
+        assert_eq!(rows[1]["style"], Value::Null);
+    }
+}
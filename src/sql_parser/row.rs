@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use docx_rs::{Document, DocumentChild, HeightRule, TableChild, TableRow};
+use futures::stream;
+use gluesql::{
+    core::{
+        ast::ColumnDef,
+        data::{Schema, Value},
+        store::{DataRow, RowIter},
+    },
+    prelude::{DataType, Key, Result},
+};
+
+use super::cell::{json_hash, tables};
+use std::{mem, str::FromStr};
+
+pub struct Row;
+
+impl Row {
+    pub fn table_name(&self) -> String {
+        "row".to_string()
+    }
+
+    pub fn fetch_all_schemas(&self) -> Vec<Schema> {
+        vec![Schema {
+            table_name: "row".to_string(),
+            column_defs: Some(vec![
+                ColumnDef {
+                    name: "hash".to_string(),
+                    data_type: DataType::Text,
+                    nullable: false,
+                    default: None,
+                    unique: None,
+                    comment: Some("行的哈希".to_string()),
+                },
+                ColumnDef {
+                    name: "table_hash".to_string(),
+                    data_type: DataType::Text,
+                    nullable: false,
+                    default: None,
+                    unique: None,
+                    comment: Some("表格的哈希".to_string()),
+                },
+                ColumnDef {
+                    name: "cell_number".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: false,
+                    default: None,
+                    unique: None,
+                    comment: Some("单元格数".to_string()),
+                },
+                ColumnDef {
+                    name: "cant_split".to_string(),
+                    data_type: DataType::Boolean,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("禁止跨页断行".to_string()),
+                },
+                ColumnDef {
+                    name: "row_height".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("行高，单位twip，为空时自动行高".to_string()),
+                },
+                ColumnDef {
+                    name: "row_height_rule".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("行高规则，atLeast或exact".to_string()),
+                },
+            ]),
+            indexes: vec![],
+            engine: None,
+            foreign_keys: vec![],
+            comment: None,
+        }]
+    }
+
+    pub async fn fetch_data(&self, docx: &Document, key: &Key) -> Result<Option<DataRow>> {
+        // 只计算行的hash，命中后再生成这一行
+        for t_box in tables(docx) {
+            for TableChild::TableRow(table_row) in &t_box.rows {
+                if Key::Str(json_hash(table_row)) == *key {
+                    return Ok(Some(row_data(&json_hash(t_box), table_row).1));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // 按需生成每行，只读取部分行时不会处理整个文档
+    pub async fn scan_data<'a>(&self, docx: &'a Document) -> Result<RowIter<'a>> {
+        let rows = tables(docx).flat_map(|t_box| {
+            let table_hash_hex = json_hash(t_box);
+            t_box
+                .rows
+                .iter()
+                .map(move |TableChild::TableRow(table_row)| {
+                    Ok(row_data(&table_hash_hex, table_row))
+                })
+        });
+        Ok(Box::pin(stream::iter(rows)))
+    }
+
+    pub async fn insert_data(&self, docx: &mut Document, _rows: Vec<(Key, DataRow)>) -> Result<()> {
+        // 查找
+        for doc_child in &mut docx.children {
+            if let DocumentChild::Table(t_box) = doc_child {
+                // 遍历行
+                for row in &mut t_box.rows {
+                    let TableChild::TableRow(table_row) = row;
+                    let hash_key = Key::Str(json_hash(table_row));
+
+                    for row in &_rows {
+                        if row.0 == hash_key
+                            && let DataRow::Map(kvs) = &row.1
+                        {
+                            for kv in kvs.iter() {
+                                if kv.0 == "cant_split"
+                                    && let Value::Bool(cant_split) = kv.1
+                                {
+                                    let property = mem::take(&mut table_row.property);
+                                    table_row.property = if *cant_split {
+                                        property.cant_split()
+                                    } else {
+                                        let mut property = property;
+                                        property.cant_split = None;
+                                        property
+                                    };
+                                }
+                            }
+
+                            // 行高
+                            match kvs.get("row_height") {
+                                Some(Value::U32(row_height)) => {
+                                    let height_rule = match kvs.get("row_height_rule") {
+                                        Some(Value::Str(rule)) => {
+                                            HeightRule::from_str(rule).unwrap_or_default()
+                                        }
+                                        _ => HeightRule::default(),
+                                    };
+                                    let property = mem::take(&mut table_row.property);
+                                    table_row.property = property
+                                        .row_height(*row_height as f32)
+                                        .height_rule(height_rule);
+                                }
+                                Some(Value::Null) => {
+                                    // 无法移除行高，改为自动行高
+                                    let has_height = serde_json::to_value(&table_row.property)
+                                        .map(|item| item.get("rowHeight").is_some())
+                                        .unwrap_or(false);
+                                    if has_height {
+                                        let property = mem::take(&mut table_row.property);
+                                        table_row.property = property.height_rule(HeightRule::Auto);
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// 表格行对应的一行数据
+fn row_data(table_hash_hex: &str, table_row: &TableRow) -> (Key, DataRow) {
+    let row_hash_hex = json_hash(table_row);
+
+    // 使用json读取属性
+    let property_value: serde_json::Value =
+        serde_json::to_value(&table_row.property).unwrap_or(serde_json::Value::Null);
+
+    let key = Key::Str(row_hash_hex.clone());
+    let mut hm: HashMap<String, Value> = HashMap::new();
+    hm.insert("hash".to_string(), Value::Str(row_hash_hex.clone()));
+    hm.insert(
+        "table_hash".to_string(),
+        Value::Str(table_hash_hex.to_string()),
+    );
+    hm.insert(
+        "cell_number".to_string(),
+        Value::U32(table_row.cells.len() as u32),
+    );
+    hm.insert(
+        "cant_split".to_string(),
+        Value::Bool(property_value.get("cantSplit").is_some()),
+    );
+
+    // 行高规则为auto时忽略行高
+    let height_rule = property_value
+        .get("heightRule")
+        .and_then(|item| item.as_str())
+        .unwrap_or("atLeast");
+    let row_height = property_value
+        .get("rowHeight")
+        .and_then(|item| item.as_f64())
+        .filter(|_| height_rule != "auto");
+    hm.insert(
+        "row_height".to_string(),
+        row_height
+            .map(|item| Value::U32(item as u32))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "row_height_rule".to_string(),
+        row_height
+            .map(|_| Value::Str(height_rule.to_string()))
+            .unwrap_or(Value::Null),
+    );
+
+    (key, DataRow::Map(hm))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql_parser::test_util::{execute, scan, table_docx};
+    use docx_rs::BuildXML;
+    use gluesql::core::data::Value;
+
+    #[test]
+    fn update_cant_split() {
+        let mut docx = table_docx(&[&["a"], &["b"]]);
+        assert_eq!(scan(&mut docx, "row")[0]["cant_split"], Value::Bool(false));
+
+        let hash = match &scan(&mut docx, "row")[1]["hash"] {
+            Value::Str(hash) => hash.clone(),
+            _ => unreachable!(),
+        };
+        execute(
+            &mut docx,
+            &format!("update row set cant_split = true where hash = '{hash}'"),
+        );
+        let xml = String::from_utf8(docx.document.build()).unwrap();
+        assert_eq!(xml.matches("<w:cantSplit").count(), 1);
+
+        execute(&mut docx, "update row set cant_split = false");
+        let xml = String::from_utf8(docx.document.build()).unwrap();
+        assert!(!xml.contains("<w:cantSplit"));
+    }
+}
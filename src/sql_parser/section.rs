@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use docx_rs::{Document, DocumentChild, SectionProperty};
+use futures::stream;
+use gluesql::{
+    core::{
+        ast::ColumnDef,
+        data::{Schema, Value},
+        store::{DataRow, RowIter},
+    },
+    prelude::{DataType, Key, Result},
+};
+
+use super::document::page_size;
+
+// 文档的节，只读
+// 除最后一节外，节的属性保存在该节最后一个段落中，最后一节的属性保存在body末尾
+pub struct Section;
+
+impl Section {
+    pub fn table_name(&self) -> String {
+        "section".to_string()
+    }
+
+    pub fn fetch_all_schemas(&self) -> Vec<Schema> {
+        vec![Schema {
+            table_name: "section".to_string(),
+            column_defs: Some(vec![
+                ColumnDef {
+                    name: "index".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("节的序号，从0开始".to_string()),
+                },
+                ColumnDef {
+                    name: "page_width".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("页面宽度，单位twip".to_string()),
+                },
+                ColumnDef {
+                    name: "page_height".to_string(),
+                    data_type: DataType::Uint32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("页面高度，单位twip".to_string()),
+                },
+                ColumnDef {
+                    name: "orientation".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("纸张方向，portrait或landscape".to_string()),
+                },
+                ColumnDef {
+                    name: "margin_top".to_string(),
+                    data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("上边距，单位twip".to_string()),
+                },
+                ColumnDef {
+                    name: "margin_bottom".to_string(),
+                    data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("下边距，单位twip".to_string()),
+                },
+                ColumnDef {
+                    name: "margin_left".to_string(),
+                    data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("左边距，单位twip".to_string()),
+                },
+                ColumnDef {
+                    name: "margin_right".to_string(),
+                    data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("右边距，单位twip".to_string()),
+                },
+            ]),
+            indexes: vec![],
+            engine: None,
+            foreign_keys: vec![],
+            comment: None,
+        }]
+    }
+
+    pub async fn fetch_data(&self, docx: &Document, key: &Key) -> Result<Option<DataRow>> {
+        // key是序号，直接定位，只生成这一行
+        let Key::U32(index) = key else {
+            return Ok(None);
+        };
+        let index = *index as usize;
+        Ok(sections(docx)
+            .nth(index)
+            .map(|section_property| section_row(index, section_property).1))
+    }
+
+    pub async fn scan_data<'a>(&self, docx: &Document) -> Result<RowIter<'a>> {
+        let rows = sections(docx)
+            .enumerate()
+            .map(|(index, section_property)| Ok(section_row(index, section_property)))
+            .collect::<Vec<_>>();
+        Ok(Box::pin(stream::iter(rows)))
+    }
+}
+
+// 按顺序返回文档的所有节
+fn sections(docx: &Document) -> impl Iterator<Item = &SectionProperty> {
+    docx.children
+        .iter()
+        .filter_map(|doc_child| match doc_child {
+            DocumentChild::Paragraph(paragraph) => paragraph.property.section_property.as_ref(),
+            _ => None,
+        })
+        .chain(std::iter::once(&docx.section_property))
+}
+
+fn section_row(index: usize, section_property: &SectionProperty) -> (Key, DataRow) {
+    let (page_width, page_height, orientation) = page_size(section_property);
+    let margin = &section_property.page_margin;
+
+    let mut hm: HashMap<String, Value> = HashMap::new();
+    hm.insert("index".to_string(), Value::U32(index as u32));
+    hm.insert("page_width".to_string(), Value::U32(page_width));
+    hm.insert("page_height".to_string(), Value::U32(page_height));
+    hm.insert("orientation".to_string(), Value::Str(orientation));
+    hm.insert("margin_top".to_string(), Value::I32(margin.top));
+    hm.insert("margin_bottom".to_string(), Value::I32(margin.bottom));
+    hm.insert("margin_left".to_string(), Value::I32(margin.left));
+    hm.insert("margin_right".to_string(), Value::I32(margin.right));
+    (Key::U32(index as u32), DataRow::Map(hm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_parser::test_util::scan;
+    use docx_rs::{Docx, PageMargin, PageOrientationType, PageSize, Paragraph, Run};
+
+    #[test]
+    fn scan_multiple_sections() {
+        // 第一节横向，第二节使用文档默认的纵向
+        let landscape = SectionProperty::new()
+            .page_size(
+                PageSize::new()
+                    .size(16838, 11906)
+                    .orient(PageOrientationType::Landscape),
+            )
+            .page_margin(
+                PageMargin::new()
+                    .top(720)
+                    .bottom(720)
+                    .left(1000)
+                    .right(1000),
+            );
+        let mut paragraph = Paragraph::new().add_run(Run::new().add_text("横向"));
+        paragraph.property = paragraph.property.section_property(landscape);
+        let mut docx = Docx::new()
+            .add_paragraph(paragraph)
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("纵向")));
+
+        let rows = scan(&mut docx, "section");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["index"], Value::U32(0));
+        assert_eq!(rows[0]["orientation"], Value::Str("landscape".to_string()));
+        assert_eq!(rows[0]["page_width"], Value::U32(16838));
+        assert_eq!(rows[0]["margin_left"], Value::I32(1000));
+        assert_eq!(rows[1]["index"], Value::U32(1));
+        assert_eq!(rows[1]["orientation"], Value::Str("portrait".to_string()));
+        assert_eq!(rows[1]["margin_top"], Value::I32(1985));
+    }
+}
@@ -2,10 +2,11 @@ use std::{collections::HashMap, str::FromStr};
 
 use async_trait::async_trait;
 use docx_rs::{
-    BorderType, Document, DocumentChild, Docx, Justification, TableAlignmentType, TableBorder,
-    TableBorderPosition, TableChild, TableProperty, WidthType, read_docx,
+    BorderType, BuildXML, Document, DocumentChild, Docx, Justification, Table, TableAlignmentType,
+    TableBorder, TableBorderPosition, TableChild, TableLayoutType, TableProperty, TableRowChild,
+    WidthType, read_docx,
 };
-use futures::stream::{self, StreamExt};
+use futures::stream;
 use gluesql::{
     core::{
         ast::ColumnDef,
@@ -19,6 +20,8 @@ use gluesql::{
     prelude::{DataType, Error, Key, Result},
 };
 use sha2::{Digest, Sha256};
+
+use super::cell::{json_hash, tables};
 use std::mem;
 
 pub struct Tables;
@@ -128,6 +131,32 @@ impl Tables {
                     unique: None,
                     comment: Some("垂直内部边框".to_string()),
                 },
+                ColumnDef {
+                    name: "style".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("表格样式id，如TableGrid".to_string()),
+                },
+                ColumnDef {
+                    name: "layout".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some(
+                        "表格布局，autofit或fixed，fixed时单元格宽度才会严格生效".to_string(),
+                    ),
+                },
+                ColumnDef {
+                    name: "distribute_width".to_string(),
+                    data_type: DataType::Boolean,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some("更新时按表格宽度等比例调整单元格宽度".to_string()),
+                },
                 ColumnDef {
                     name: "json_content".to_string(),
                     data_type: DataType::Text,
@@ -136,6 +165,17 @@ impl Tables {
                     unique: None,
                     comment: Some("表格的json形式".to_string()),
                 },
+                ColumnDef {
+                    name: "xml_content".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                    default: None,
+                    unique: None,
+                    comment: Some(
+                        "表格序列化后的OOXML（<w:tbl>），只读，用于排查渲染问题，DocxDb开启xml_content时才生成"
+                            .to_string(),
+                    ),
+                },
             ]),
             indexes: vec![],
             engine: None,
@@ -144,150 +184,26 @@ impl Tables {
         }]
     }
 
-    pub async fn fetch_data(&self, docx: &Document, key: &Key) -> Result<Option<DataRow>> {
-        // 查找
-        if let Ok(mut rowIter) = self.scan_data(docx).await {
-            while let Some(row_result) = rowIter.next().await {
-                if let Ok(row) = row_result {
-                    if row.0 == *key {
-                        return Ok(Some(row.1.clone()));
-                    }
-                }
-            }
-        }
-
-        return Result::Ok(None);
+    pub async fn fetch_data(
+        &self,
+        docx: &Document,
+        key: &Key,
+        xml_content: bool,
+    ) -> Result<Option<DataRow>> {
+        // 只计算hash，命中后再生成这一行
+        Ok(tables(docx)
+            .find(|t_box| Key::Str(json_hash(t_box)) == *key)
+            .map(|t_box| table_row(t_box, xml_content).1))
     }
 
-    // todo 修改为stream格式
-    pub async fn scan_data<'a>(&self, docx: &Document) -> Result<RowIter<'a>> {
-        let mut tables = Vec::new();
-        for doc_child in &docx.children {
-            if let DocumentChild::Table(t_box) = doc_child {
-                let table_json_str = serde_json::to_string(t_box).unwrap_or("".to_string());
-                let mut hasher = Sha256::new();
-                hasher.update(table_json_str.as_bytes());
-                let result = hasher.finalize();
-                let hash_hex = hex::encode(result);
-
-                // 表格的行数和列数
-                let row_number = t_box.rows.len();
-                let column_number = t_box
-                    .rows
-                    .get(0)
-                    .map(|item| {
-                        if let TableChild::TableRow(table_row) = item {
-                            return table_row.cells.len();
-                        } else {
-                            return 0;
-                        }
-                    })
-                    .unwrap_or(0);
-
-                // 使用json读取属性
-                let property_value: serde_json::Value =
-                    serde_json::to_value(&t_box.property).unwrap_or(serde_json::Value::Null);
-
-                let key = Key::Str(hash_hex.clone());
-                let mut hm: HashMap<String, Value> = HashMap::new();
-                hm.insert("hash".to_string(), Value::Str(hash_hex.clone()));
-                hm.insert("row_number".to_string(), Value::I32(row_number as i32));
-                hm.insert(
-                    "column_number".to_string(),
-                    Value::U32(column_number as u32),
-                );
-                hm.insert(
-                    "width".to_string(),
-                    Value::U32(
-                        property_value
-                            .get("width")
-                            .and_then(|item| item.get("width"))
-                            .and_then(|item| item.as_u64())
-                            .and_then(|item| Some(item as u32))
-                            .unwrap_or(0u32),
-                    ),
-                );
-                hm.insert(
-                    "width_type".to_string(),
-                    Value::Str(
-                        property_value
-                            .get("width")
-                            .and_then(|item| item.get("widthType"))
-                            .and_then(|item| item.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                    ),
-                );
-                hm.insert(
-                    "justification".to_string(),
-                    Value::Str(
-                        property_value
-                            .get("justification")
-                            .and_then(|item| item.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                    ),
-                );
-                hm.insert(
-                    "borders_top".to_string(),
-                    property_value
-                        .get("borders")
-                        .and_then(|item| item.get("top"))
-                        .and_then(|item| item.as_str())
-                        .map(|item| Value::Str(item.to_string()))
-                        .unwrap_or(Value::Null),
-                );
-                hm.insert(
-                    "borders_left".to_string(),
-                    property_value
-                        .get("borders")
-                        .and_then(|item| item.get("left"))
-                        .and_then(|item| item.as_str())
-                        .map(|item| Value::Str(item.to_string()))
-                        .unwrap_or(Value::Null),
-                );
-                hm.insert(
-                    "borders_bottom".to_string(),
-                    property_value
-                        .get("borders")
-                        .and_then(|item| item.get("bottom"))
-                        .and_then(|item| item.as_str())
-                        .map(|item| Value::Str(item.to_string()))
-                        .unwrap_or(Value::Null),
-                );
-                hm.insert(
-                    "borders_right".to_string(),
-                    property_value
-                        .get("borders")
-                        .and_then(|item| item.get("right"))
-                        .and_then(|item| item.as_str())
-                        .map(|item| Value::Str(item.to_string()))
-                        .unwrap_or(Value::Null),
-                );
-                hm.insert(
-                    "borders_inside_h".to_string(),
-                    property_value
-                        .get("borders")
-                        .and_then(|item| item.get("insideH"))
-                        .and_then(|item| item.as_str())
-                        .map(|item| Value::Str(item.to_string()))
-                        .unwrap_or(Value::Null),
-                );
-                hm.insert(
-                    "borders_inside_v".to_string(),
-                    property_value
-                        .get("borders")
-                        .and_then(|item| item.get("insideV"))
-                        .and_then(|item| item.as_str())
-                        .map(|item| Value::Str(item.to_string()))
-                        .unwrap_or(Value::Null),
-                );
-
-                let data_row = DataRow::Map(hm);
-                tables.push(Ok((key, data_row)));
-            }
-        }
-        return Ok(Box::pin(stream::iter(tables)));
+    // 按需生成每行，只读取部分行时不会处理整个文档
+    pub async fn scan_data<'a>(
+        &self,
+        docx: &'a Document,
+        xml_content: bool,
+    ) -> Result<RowIter<'a>> {
+        let rows = tables(docx).map(move |t_box| Ok(table_row(t_box, xml_content)));
+        Ok(Box::pin(stream::iter(rows)))
     }
 
     pub async fn insert_data(&self, docx: &mut Document, _rows: Vec<(Key, DataRow)>) -> Result<()> {
@@ -358,6 +274,23 @@ impl Tables {
                                         }
                                     }
                                 }
+                                // 样式id不做检查，未知的样式Word会忽略
+                                if kv.0 == "style"
+                                    && let Value::Str(style) = kv.1
+                                {
+                                    let property = mem::take(&mut t_box.property);
+                                    t_box.property = property.style(style);
+                                }
+                                // 只接受autofit和fixed，其他值忽略；与当前布局相同时不修改，保持hash不变
+                                if kv.0 == "layout"
+                                    && let Value::Str(layout) = kv.1
+                                    && (layout == "autofit" || layout == "fixed")
+                                    && *layout != table_layout(&t_box.property)
+                                    && let Ok(layout_type) = TableLayoutType::from_str(layout)
+                                {
+                                    let property = mem::take(&mut t_box.property);
+                                    t_box.property = property.layout(layout_type);
+                                }
                                 if kv.0 == "borders_top" {
                                     if let Value::Str(border_value) = kv.1 {
                                         let property = mem::take(&mut t_box.property);
@@ -419,6 +352,11 @@ impl Tables {
                                     }
                                 }
                             }
+
+                            // 按表格宽度等比例调整单元格宽度
+                            if let Some(Value::Bool(true)) = kvs.get("distribute_width") {
+                                self.distribute_width(t_box);
+                            }
                         }
                     }
                 }
@@ -428,6 +366,71 @@ impl Tables {
         Ok(())
     }
 
+    // 等比例调整tblGrid和每行单元格的宽度，使其总和等于表格宽度
+    // 单元格原来都没有宽度时按gridSpan平均分配
+    fn distribute_width(&self, table: &mut Table) {
+        // 使用json读取属性
+        let property_value: serde_json::Value =
+            serde_json::to_value(&table.property).unwrap_or(serde_json::Value::Null);
+        let table_width = property_value
+            .get("width")
+            .and_then(|item| item.get("width"))
+            .and_then(|item| item.as_u64())
+            .unwrap_or(0) as usize;
+        if table_width == 0 {
+            return;
+        }
+        let width_type = match property_value
+            .get("width")
+            .and_then(|item| item.get("widthType"))
+            .and_then(|item| item.as_str())
+            .and_then(|item| WidthType::from_str(item).ok())
+        {
+            Some(WidthType::Pct) => WidthType::Pct,
+            _ => WidthType::Dxa,
+        };
+
+        // tblGrid的单位总是dxa，表格宽度为pct（5000为100%）时不缩放
+        if width_type == WidthType::Dxa && !table.grid.is_empty() {
+            table.grid = scale_widths(&table.grid, table_width);
+        }
+
+        for TableChild::TableRow(table_row) in &mut table.rows {
+            let mut widths = vec![];
+            let mut spans = vec![];
+            for TableRowChild::TableCell(table_cell) in &table_row.cells {
+                let cell_property: serde_json::Value =
+                    serde_json::to_value(&table_cell.property).unwrap_or(serde_json::Value::Null);
+                widths.push(
+                    cell_property
+                        .get("width")
+                        .and_then(|item| item.get("width"))
+                        .and_then(|item| item.as_u64())
+                        .unwrap_or(0) as usize,
+                );
+                spans.push(
+                    cell_property
+                        .get("gridSpan")
+                        .and_then(|item| item.as_u64())
+                        .unwrap_or(1) as usize,
+                );
+            }
+
+            let weights = if widths.iter().sum::<usize>() > 0 {
+                widths
+            } else {
+                spans
+            };
+            let new_widths = scale_widths(&weights, table_width);
+            for (TableRowChild::TableCell(table_cell), width) in
+                table_row.cells.iter_mut().zip(new_widths)
+            {
+                let property = mem::take(&mut table_cell.property);
+                table_cell.property = property.width(width, width_type);
+            }
+        }
+    }
+
     fn set_border(
         &self,
         property: TableProperty,
@@ -438,31 +441,413 @@ impl Tables {
         let value: serde_json::Value =
             serde_json::from_str(&border_value).unwrap_or(serde_json::Value::Null);
 
+        // 当前位置已有的边框，只覆盖传入的属性
+        let position_key =
+            serde_json::to_value(&border_position).unwrap_or(serde_json::Value::Null);
+        let current = serde_json::to_value(&property)
+            .ok()
+            .and_then(|item| {
+                item.get("borders")
+                    .and_then(|borders| borders.get(position_key.as_str().unwrap_or("")))
+                    .cloned()
+            })
+            .unwrap_or(serde_json::Value::Null);
+
         let mut table_border = TableBorder::new(border_position);
+        for value in [current, value] {
+            // 颜色
+            if let Some(color) = value.get("color").and_then(|item| item.as_str()) {
+                table_border = table_border.color(color);
+            }
 
-        // 颜色
-        if let Some(color) = value.get("color").and_then(|item| item.as_str()) {
-            table_border = table_border.color(color);
-        }
+            // 线条宽度
+            if let Some(size) = value
+                .get("size")
+                .and_then(|item| item.as_u64())
+                .and_then(|item| Some(item as usize))
+            {
+                table_border = table_border.size(size);
+            }
 
-        // 线条宽度
-        if let Some(size) = value
-            .get("size")
-            .and_then(|item| item.as_u64())
-            .and_then(|item| Some(item as usize))
-        {
-            table_border = table_border.size(size);
+            // 线条类型
+            if let Some(border_type) = value
+                .get("borderType")
+                .and_then(|item| item.as_str())
+                .and_then(|item| BorderType::from_str(item).ok())
+            {
+                table_border = table_border.border_type(border_type);
+            }
         }
 
-        // 线条类型
-        if let Some(border_type) = value
-            .get("borderType")
+        return property.set_border(table_border);
+    }
+}
+
+// 表格对应的一行数据，xml_content为false时不生成xml_content列
+fn table_row(t_box: &Table, xml_content: bool) -> (Key, DataRow) {
+    let hash_hex = json_hash(t_box);
+
+    // 表格的行数和列数
+    let row_number = t_box.rows.len();
+    let column_number = t_box
+        .rows
+        .first()
+        .map(|TableChild::TableRow(table_row)| table_row.cells.len())
+        .unwrap_or(0);
+
+    // 使用json读取属性
+    let property_value: serde_json::Value =
+        serde_json::to_value(&t_box.property).unwrap_or(serde_json::Value::Null);
+
+    let key = Key::Str(hash_hex.clone());
+    let mut hm: HashMap<String, Value> = HashMap::new();
+    hm.insert("hash".to_string(), Value::Str(hash_hex.clone()));
+    hm.insert("row_number".to_string(), Value::I32(row_number as i32));
+    hm.insert(
+        "column_number".to_string(),
+        Value::U32(column_number as u32),
+    );
+    hm.insert(
+        "width".to_string(),
+        Value::U32(
+            property_value
+                .get("width")
+                .and_then(|item| item.get("width"))
+                .and_then(|item| item.as_u64())
+                .map(|item| item as u32)
+                .unwrap_or(0u32),
+        ),
+    );
+    hm.insert(
+        "width_type".to_string(),
+        Value::Str(
+            property_value
+                .get("width")
+                .and_then(|item| item.get("widthType"))
+                .and_then(|item| item.as_str())
+                .unwrap_or("")
+                .to_string(),
+        ),
+    );
+    hm.insert(
+        "justification".to_string(),
+        Value::Str(
+            property_value
+                .get("justification")
+                .and_then(|item| item.as_str())
+                .unwrap_or("")
+                .to_string(),
+        ),
+    );
+    hm.insert(
+        "style".to_string(),
+        property_value
+            .get("style")
             .and_then(|item| item.as_str())
-            .and_then(|item| BorderType::from_str(item).ok())
-        {
-            table_border = table_border.border_type(border_type);
-        }
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "layout".to_string(),
+        Value::Str(table_layout(&t_box.property)),
+    );
+    hm.insert(
+        "borders_top".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("top"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_left".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("left"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_bottom".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("bottom"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_right".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("right"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_inside_h".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("insideH"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    hm.insert(
+        "borders_inside_v".to_string(),
+        property_value
+            .get("borders")
+            .and_then(|item| item.get("insideV"))
+            .map(|item| Value::Str(item.to_string()))
+            .unwrap_or(Value::Null),
+    );
 
-        return property.set_border(table_border);
+    hm.insert("distribute_width".to_string(), Value::Bool(false));
+    // gluesql扫描时不区分查询的列，开启后每行都会生成，默认为null
+    hm.insert(
+        "xml_content".to_string(),
+        if xml_content {
+            Value::Str(String::from_utf8_lossy(&t_box.build()).to_string())
+        } else {
+            Value::Null
+        },
+    );
+
+    (key, DataRow::Map(hm))
+}
+
+// 表格布局，未设置时Word按autofit处理
+fn table_layout(property: &TableProperty) -> String {
+    serde_json::to_value(property)
+        .ok()
+        .and_then(|item| {
+            item.get("layout")
+                .and_then(|item| item.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or("autofit".to_string())
+}
+
+// 按权重把total分配到每一项，舍入的误差补到最后一项
+fn scale_widths(weights: &[usize], total: usize) -> Vec<usize> {
+    let weight_sum: usize = weights.iter().sum();
+    if weight_sum == 0 {
+        return weights.to_vec();
+    }
+    let mut widths = weights
+        .iter()
+        .map(|weight| weight * total / weight_sum)
+        .collect::<Vec<usize>>();
+    let assigned: usize = widths.iter().sum();
+    if let Some(last) = widths.last_mut() {
+        *last += total - assigned;
+    }
+    widths
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::docx_util::read::{pack_to_vec, read_docx_checked};
+    use crate::sql_parser::{
+        DocxDb,
+        test_util::{
+            embedded_images, execute, image_docx, merged_table_docx, scan, table_docx, text_cell,
+        },
+    };
+    use docx_rs::{BuildXML, DocumentChild, Docx, Paragraph, Run, Table, TableRow, WidthType};
+    use futures::executor::block_on;
+    use gluesql::{
+        core::{
+            data::Value,
+            store::{DataRow, Store},
+        },
+        prelude::{Glue, Key, Payload},
+    };
+
+    #[test]
+    fn hash_is_stable_across_scan_and_noop_update() {
+        let mut docx = table_docx(&[&["a", "b"], &["c", "d"]]);
+        let before = scan(&mut docx, "tables");
+        assert_eq!(before, scan(&mut docx, "tables"));
+
+        execute(&mut docx, "update tables set width_type = width_type");
+        assert_eq!(scan(&mut docx, "tables")[0]["hash"], before[0]["hash"]);
+    }
+
+    #[test]
+    fn update_table_style() {
+        let mut docx = table_docx(&[&["a"]]);
+        assert_eq!(scan(&mut docx, "tables")[0]["style"], Value::Null);
+
+        execute(&mut docx, "update tables set style = 'TableGrid'");
+        assert_eq!(
+            scan(&mut docx, "tables")[0]["style"],
+            Value::Str("TableGrid".to_string())
+        );
+    }
+
+    #[test]
+    fn update_table_layout() {
+        let mut docx = table_docx(&[&["a"]]);
+        assert_eq!(
+            scan(&mut docx, "tables")[0]["layout"],
+            Value::Str("autofit".to_string())
+        );
+
+        execute(&mut docx, "update tables set layout = 'fixed'");
+        assert_eq!(
+            scan(&mut docx, "tables")[0]["layout"],
+            Value::Str("fixed".to_string())
+        );
+        let xml = String::from_utf8(docx.document.build()).unwrap();
+        assert!(xml.contains(r#"<w:tblLayout w:type="fixed" />"#));
+
+        // 不支持的值不修改
+        execute(&mut docx, "update tables set layout = 'auto'");
+        assert_eq!(
+            scan(&mut docx, "tables")[0]["layout"],
+            Value::Str("fixed".to_string())
+        );
+    }
+
+    #[test]
+    fn select_xml_content() {
+        let mut docx = table_docx(&[&["a", "b"]]);
+        // 默认不生成
+        assert_eq!(scan(&mut docx, "tables")[0]["xml_content"], Value::Null);
+        assert_eq!(scan(&mut docx, "cell")[0]["xml_content"], Value::Null);
+
+        let select = |docx: &mut Docx, sql: &str| {
+            let mut glue = Glue::new(DocxDb::new(docx).xml_content(true));
+            match block_on(glue.execute(sql)).unwrap().remove(0) {
+                Payload::Select { mut rows, .. } => rows.remove(0).remove(0),
+                _ => panic!("not select"),
+            }
+        };
+        let Value::Str(xml) = select(&mut docx, "select xml_content from tables") else {
+            panic!("应为字符串");
+        };
+        assert!(xml.starts_with("<w:tbl>"));
+        assert!(xml.contains("<w:t xml:space=\"preserve\">b</w:t>"));
+
+        let Value::Str(xml) = select(
+            &mut docx,
+            "select xml_content from cell where content = 'b'",
+        ) else {
+            panic!("应为字符串");
+        };
+        assert!(xml.starts_with("<w:tc>"));
+        assert!(!xml.contains(">a<"));
+
+        // 只读，更新时忽略
+        let before = scan(&mut docx, "tables");
+        execute(&mut docx, "update tables set xml_content = ''");
+        assert_eq!(scan(&mut docx, "tables"), before);
+    }
+
+    #[test]
+    fn scan_empty_and_merged_tables() {
+        let rows = scan(&mut table_docx(&[]), "tables");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["row_number"], Value::I32(0));
+        assert_eq!(rows[0]["column_number"], Value::U32(0));
+
+        let rows = scan(&mut merged_table_docx(), "tables");
+        assert_eq!(rows[0]["row_number"], Value::I32(2));
+        // 列数按第一行的单元格数量计算，横向合并的单元格只算一个
+        assert_eq!(rows[0]["column_number"], Value::U32(2));
+    }
+
+    #[test]
+    fn fetch_data_builds_only_matched_row() {
+        let mut docx = table_docx(&[&["a"]])
+            .add_table(docx_rs::Table::new(vec![]))
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("正文")));
+        let tables = scan(&mut docx, "tables");
+        let rows = scan(&mut docx, "row");
+        let db = DocxDb::new(&mut docx);
+        block_on(async {
+            for (table_name, row) in [("tables", &tables[1]), ("row", &rows[0])] {
+                let Value::Str(hash) = &row["hash"] else {
+                    unreachable!()
+                };
+                let fetched = db.fetch_data(table_name, &Key::Str(hash.clone())).await;
+                assert_eq!(fetched.unwrap(), Some(DataRow::Map(row.clone())));
+            }
+            let missing = db.fetch_data("tables", &Key::Str("none".to_string())).await;
+            assert_eq!(missing.unwrap(), None);
+
+            // 段落按序号定位
+            let Some(DataRow::Map(paragraph)) =
+                db.fetch_data("paragraph", &Key::U32(0)).await.unwrap()
+            else {
+                panic!("应为第一个段落");
+            };
+            assert_eq!(paragraph["text"], Value::Str("正文".to_string()));
+            let missing = db.fetch_data("paragraph", &Key::U32(1)).await;
+            assert_eq!(missing.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn update_keeps_images() {
+        // 模拟sql命令：读取、执行update后保存
+        let docx = image_docx(&[("rId4", b"logo"), ("rId5", b"figure")])
+            .add_table(Table::new(vec![TableRow::new(vec![text_cell("a")])]));
+        let mut docx = read_docx_checked(&pack_to_vec(docx).unwrap()).unwrap();
+        execute(&mut docx, "update tables set width = 9000");
+        assert_eq!(scan(&mut docx, "tables")[0]["width"], Value::U32(9000));
+
+        let docx_bytes = pack_to_vec(docx).unwrap();
+        assert_eq!(
+            embedded_images(&docx_bytes),
+            vec![b"logo".to_vec(), b"figure".to_vec()]
+        );
+    }
+
+    #[test]
+    fn scale_widths_by_weight() {
+        // 舍入的误差补到最后一项
+        assert_eq!(super::scale_widths(&[1, 1, 1], 100), vec![33, 33, 34]);
+        assert_eq!(super::scale_widths(&[2000, 1000], 9000), vec![6000, 3000]);
+        assert_eq!(super::scale_widths(&[0, 0], 100), vec![0, 0]);
+        assert_eq!(super::scale_widths(&[], 100), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn distribute_width_by_grid_span() {
+        let table = |width: usize, width_type: WidthType| {
+            Docx::new().add_table(
+                Table::new(vec![
+                    TableRow::new(vec![text_cell("a").grid_span(2), text_cell("b")]),
+                    TableRow::new(vec![text_cell("c"), text_cell("d"), text_cell("e")]),
+                ])
+                .set_grid(vec![1000, 1000, 2000])
+                .width(width, width_type),
+            )
+        };
+
+        // 单元格都没有宽度时按gridSpan分配
+        let mut docx = table(9000, WidthType::Dxa);
+        execute(&mut docx, "update tables set distribute_width = true");
+        let DocumentChild::Table(t_box) = &docx.document.children[0] else {
+            unreachable!()
+        };
+        assert_eq!(t_box.grid, vec![2250, 2250, 4500]);
+        let xml = String::from_utf8(docx.document.build()).unwrap();
+        assert!(xml.contains(r#"<w:tcW w:w="6000" w:type="dxa" />"#));
+        assert_eq!(
+            xml.matches(r#"<w:tcW w:w="3000" w:type="dxa" />"#).count(),
+            4
+        );
+
+        // pct时单元格按5000分配，tblGrid保持不变
+        let mut docx = table(5000, WidthType::Pct);
+        execute(&mut docx, "update tables set distribute_width = true");
+        let DocumentChild::Table(t_box) = &docx.document.children[0] else {
+            unreachable!()
+        };
+        assert_eq!(t_box.grid, vec![1000, 1000, 2000]);
+        let xml = String::from_utf8(docx.document.build()).unwrap();
+        assert!(xml.contains(r#"<w:tcW w:w="3333" w:type="pct" />"#));
+        assert!(xml.contains(r#"<w:tcW w:w="1667" w:type="pct" />"#));
     }
 }
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use docx_rs::{Docx, Paragraph, Pic, Run, Table, TableCell, TableRow, VMergeType};
+use futures::{StreamExt, executor::block_on};
+use gluesql::{
+    core::{
+        data::Value,
+        store::{DataRow, Store},
+    },
+    prelude::Glue,
+};
+
+use regex::Regex;
+
+use super::DocxDb;
+use crate::docx_util::read::document_images;
+
+// 文本单元格
+pub fn text_cell(text: &str) -> TableCell {
+    TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)))
+}
+
+// 按二维文本构造只有一个表格的docx，空数组得到没有行的表格
+pub fn table_docx(rows: &[&[&str]]) -> Docx {
+    let rows = rows
+        .iter()
+        .map(|cells| TableRow::new(cells.iter().map(|text| text_cell(text)).collect()))
+        .collect();
+    Docx::new().add_table(Table::new(rows))
+}
+
+// 包含合并单元格的表格：第一行横向合并两列，第一列纵向合并两行
+pub fn merged_table_docx() -> Docx {
+    Docx::new().add_table(Table::new(vec![
+        TableRow::new(vec![
+            text_cell("a").vertical_merge(VMergeType::Restart),
+            text_cell("b").grid_span(2),
+        ]),
+        TableRow::new(vec![
+            TableCell::new().vertical_merge(VMergeType::Continue),
+            text_cell("c"),
+            text_cell("d"),
+        ]),
+    ]))
+}
+
+// 读取虚拟表的所有行
+pub fn scan(docx: &mut Docx, table_name: &str) -> Vec<HashMap<String, Value>> {
+    let db = DocxDb::new(docx);
+    block_on(async {
+        let rows = db.scan_data(table_name).await.unwrap();
+        rows.map(|item| match item.unwrap().1 {
+            DataRow::Map(hm) => hm,
+            _ => HashMap::new(),
+        })
+        .collect::<Vec<_>>()
+        .await
+    })
+}
+
+// 执行sql，失败时panic
+pub fn execute(docx: &mut Docx, sql: &str) {
+    let mut glue = Glue::new(DocxDb::new(docx));
+    block_on(glue.execute(sql)).unwrap();
+}
+
+// 每张图片一个段落的docx，图片使用指定的关系id
+pub fn image_docx(images: &[(&str, &[u8])]) -> Docx {
+    images.iter().fold(Docx::new(), |docx, (id, content)| {
+        let mut pic = Pic::new_with_dimensions(content.to_vec(), 1, 1);
+        pic.id = id.to_string();
+        docx.add_paragraph(Paragraph::new().add_run(Run::new().add_image(pic)))
+    })
+}
+
+// docx正文中按出现顺序引用的图片内容
+pub fn embedded_images(docx_bytes: &[u8]) -> Vec<Vec<u8>> {
+    let images = document_images(docx_bytes).unwrap();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes)).unwrap();
+    let mut document = String::new();
+    std::io::Read::read_to_string(
+        &mut archive.by_name("word/document.xml").unwrap(),
+        &mut document,
+    )
+    .unwrap();
+    Regex::new(r#"r:embed="([^"]*)""#)
+        .unwrap()
+        .captures_iter(&document)
+        .map(|captures| images.get(&captures[1]).cloned().unwrap_or_default())
+        .collect()
+}
@@ -1,23 +1,265 @@
+use chrono::{Local, SecondsFormat};
 use docx_handlebars::render_handlebars;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Number, Value};
+use sha2::{Digest, Sha256};
 use std::{
     cell::Ref,
+    cmp::Ordering,
     collections::{HashMap, HashSet},
 };
 
 const SWAGGER_DOCX_MODEL: &[u8] = include_bytes!("../../asset/template/swagger-model.docx");
+const SWAGGER_DOCX_MODEL_EN: &[u8] = include_bytes!("../../asset/template/swagger-model-en.docx");
+
+// swagger文档的生成选项
+#[derive(Debug, Clone)]
+pub struct SwaggerOptions {
+    // 模板语言，zh或en
+    pub lang: String,
+
+    // 嵌套字段名的拼接方式
+    pub flatten_style: FlattenStyle,
+
+    // 引用展开的最大深度，超过后不再展开
+    pub max_depth: usize,
+
+    // 移除说明中的html标签
+    pub strip_html_descriptions: bool,
+
+    // 只保留x-since不小于该版本的接口
+    pub since: Option<String>,
+
+    // 设置since时，是否保留没有x-since的接口
+    pub include_unversioned: bool,
+
+    // 只生成这些分组，不为空时忽略exclude_tags
+    pub include_tags: Vec<String>,
+
+    // 不生成这些分组
+    pub exclude_tags: Vec<String>,
+
+    // 接口的分组方式，默认按tags
+    pub group_by: GroupBy,
+
+    // 在文档末尾生成数据模型附录
+    pub with_models: bool,
+
+    // 空的说明、数据类型、认证使用的占位符，如"-"
+    pub empty_placeholder: Option<String>,
+
+    // 给分组和接口编号，分组为章（1），接口为节（1.1）
+    pub number_sections: bool,
+
+    // swagger的来源（文件路径或url），渲染在标题下
+    pub source: Option<String>,
+
+    // 是否记录生成时间，关闭后相同输入生成的文档内容相同
+    pub timestamp: bool,
+
+    // 在正文前生成封面
+    pub cover: bool,
+
+    // 封面上的logo，base64编码的图片
+    pub logo: Option<String>,
+
+    // 按字段名生成不同的整数和布尔示例值，关闭时整数固定为1，布尔固定为false
+    pub realistic_examples: bool,
+
+    // 数组参数的类型显示元素类型，如array<string>、array<Order>
+    pub array_item_types: bool,
+
+    // 接口只返回xml时，返回示例生成为xml
+    pub xml_examples: bool,
+}
+
+impl Default for SwaggerOptions {
+    fn default() -> Self {
+        SwaggerOptions {
+            lang: "zh".to_string(),
+            flatten_style: FlattenStyle::Json,
+            max_depth: 10,
+            strip_html_descriptions: false,
+            since: None,
+            include_unversioned: false,
+            include_tags: vec![],
+            exclude_tags: vec![],
+            group_by: GroupBy::Tag,
+            with_models: false,
+            empty_placeholder: None,
+            number_sections: false,
+            source: None,
+            timestamp: true,
+            cover: false,
+            logo: None,
+            realistic_examples: false,
+            array_item_types: false,
+            xml_examples: false,
+        }
+    }
+}
+
+// 嵌套字段名的拼接方式
+// 对象字段之间都用"."连接，区别只在数组的标记：
+// Json: user.roles[].name，数组标记紧跟字段名
+// Dot:  user.roles.[].name，数组标记作为单独的一级
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlattenStyle {
+    Json,
+    Dot,
+}
+
+// 接口的分组方式
+// Tag:  按tags分组，只有tags中声明的分组才会生成
+// Path: 按路径的第一段分组，如/users/{id}分到users，适用于没有tags的swagger
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    Tag,
+    Path,
+}
+
+// 路径的第一段，根路径为"/"
+fn path_group(url: &str) -> String {
+    url.split('/')
+        .find(|item| !item.is_empty())
+        .unwrap_or("/")
+        .to_string()
+}
+
+// 拼接字段名，is_array表示name是数组，child为数组元素或对象的子字段名（可以为空）
+fn field_path(name: &str, is_array: bool, child: &str, style: FlattenStyle) -> String {
+    let mut path = name.to_string();
+    if is_array {
+        path.push_str(match style {
+            FlattenStyle::Json => "[]",
+            FlattenStyle::Dot => ".[]",
+        });
+    }
+    if !child.is_empty() {
+        path.push('.');
+        path.push_str(child);
+    }
+    path
+}
+
+// 接口的x-since是否满足options.since，未设置since时保留所有接口
+fn since_matches(since: Option<&str>, options: &SwaggerOptions) -> bool {
+    match (&options.since, since) {
+        (None, _) => true,
+        (Some(_), None) => options.include_unversioned,
+        (Some(min), Some(since)) => compare_version(since, min) != Ordering::Less,
+    }
+}
+
+// 按semver比较版本号，允许v前缀和省略的段（1.2等同1.2.0）
+// 数字段相同时，带预发布标记的版本较小（1.0.0-beta < 1.0.0）
+fn compare_version(a: &str, b: &str) -> Ordering {
+    fn parse(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim().trim_start_matches(['v', 'V']);
+        // 构建信息不参与比较
+        let version = version.split('+').next().unwrap_or("");
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let numbers = core
+            .split('.')
+            .map(|item| item.parse::<u64>().unwrap_or(0))
+            .collect();
+        (numbers, pre)
+    }
+
+    let (a_numbers, a_pre) = parse(a);
+    let (b_numbers, b_pre) = parse(b);
+    for index in 0..a_numbers.len().max(b_numbers.len()) {
+        let a_number = a_numbers.get(index).unwrap_or(&0);
+        let b_number = b_numbers.get(index).unwrap_or(&0);
+        if a_number != b_number {
+            return a_number.cmp(b_number);
+        }
+    }
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a_pre), Some(b_pre)) => a_pre.cmp(b_pre),
+    }
+}
+
+// 超过最大深度的字段说明
+const NESTED_TOO_DEEP: &str = "(嵌套过深)";
+
+// 没有定义结构的对象，可以是任意json
+const FREE_FORM_OBJECT: &str = "object (任意)";
+
+// 根据语言选择内置模板，未知语言使用中文模板
+pub fn swagger_docx_model(lang: &str) -> &'static [u8] {
+    match lang {
+        "en" => SWAGGER_DOCX_MODEL_EN,
+        _ => SWAGGER_DOCX_MODEL,
+    }
+}
 
 pub fn parse_swagger_and_gen_docx(
-    swagger_bytes: &Vec<u8>,
+    swagger_bytes: &[u8],
     output_file_name: &String,
+    options: &SwaggerOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let sw: SwaggerDocument = serde_json::from_slice(&swagger_bytes)?;
+    let result = swagger_to_docx(swagger_bytes, options)?;
+
+    // 保存
+    std::fs::write(output_file_name, result)?;
+
+    return Ok(());
+}
+
+// 解析swagger并渲染为docx的字节
+pub fn swagger_to_docx(
+    swagger_bytes: &[u8],
+    options: &SwaggerOptions,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let docx_project = parse_swagger(swagger_bytes, options)?;
+
+    // 渲染模板
+    render_swagger_docx(&docx_project, &options.lang)
+}
+
+// 解析swagger为docx的模板对象
+pub fn parse_swagger(
+    swagger_bytes: &[u8],
+    options: &SwaggerOptions,
+) -> Result<DocxProjectInfo, Box<dyn std::error::Error>> {
+    let mut sw_value: Value = serde_json::from_slice(&swagger_bytes)?;
+    resolve_parameter_refs(&mut sw_value);
+    let mut sw: SwaggerDocument = serde_json::from_value(sw_value)?;
+    if options.strip_html_descriptions {
+        strip_html_descriptions(&mut sw);
+    }
+    inherit_content_types(&mut sw);
+
+    // 接口地址前缀，如 https://host/base
+    let scheme = sw
+        .schemes
+        .as_ref()
+        .and_then(|item| item.first())
+        .map(|item| item.as_str())
+        .unwrap_or("http");
+    let base_url = format!(
+        "{scheme}://{}{}",
+        sw.host,
+        sw.basePath.as_deref().unwrap_or("").trim_end_matches('/')
+    );
 
     // 生成docx的模板对象
     let mut apis: HashMap<String, Vec<DocxApiInfo>> = HashMap::new();
-    for tag in sw.tags {
-        apis.insert(tag.name.clone(), vec![]);
+    let mut tag_descs: HashMap<String, String> = HashMap::new();
+    if options.group_by == GroupBy::Tag {
+        for tag in sw.tags {
+            apis.insert(tag.name.clone(), vec![]);
+            if let Some(desc) = tag.description.filter(|item| !item.is_empty()) {
+                tag_descs.insert(tag.name, desc);
+            }
+        }
     }
 
     for urls in sw.paths {
@@ -26,21 +268,56 @@ pub fn parse_swagger_and_gen_docx(
         for methods in method_infos {
             let method = methods.0;
             let operation = methods.1;
+            if !since_matches(operation.since.as_deref(), options) {
+                continue;
+            }
+
+            // 调用示例
+            let example_curl = build_example_curl(
+                &method,
+                &format!("{base_url}{url}"),
+                &operation,
+                &sw.definitions,
+                &sw.securityDefinitions,
+                options,
+            );
+
+            // 请求体和响应体的树形结构，与参数表格互为补充
+            let mut tree_lines: Vec<String> = vec![];
+            for param in operation.parameters.iter().flatten() {
+                if let Some(schema) = &param.schema {
+                    tree_lines.extend(schema_tree("请求 body", schema, &sw.definitions, options));
+                }
+            }
+            if binary_response_type(&operation.produces, &operation.responses).is_none()
+                && let Some(schema) = documented_response(&operation.responses)
+                    .and_then(|response| response.schema.as_ref())
+            {
+                tree_lines.extend(schema_tree("返回 body", schema, &sw.definitions, options));
+            }
+
+            // 认证说明
+            let auth = security_desc(&operation, &sw.securityDefinitions);
 
             // 请求参数
             let mut query_params: Vec<DocxParamInfo> = vec![];
             if let Some(params) = operation.parameters {
                 for param in params {
+                    let example = param_example(&param, options);
                     let param_type = param.in_;
                     if let Some(schema) = param.schema {
                         if let SchemaRef::Ref { ref_, original_ref } = schema {
                             let mut ps = param_by_definitions(
                                 &original_ref.unwrap_or("".to_string()),
                                 &sw.definitions,
+                                &mut HashSet::new(),
+                                1,
+                                options,
                             );
                             ps.iter_mut().for_each(|item| {
                                 // 在每个参数前面加上"body."
-                                item.name = format!("body.{}", item.name);
+                                item.name =
+                                    field_path("body", false, &item.name, options.flatten_style);
 
                                 // 设置param_type
                                 item.param_type = param_type.clone();
@@ -52,7 +329,11 @@ pub fn parse_swagger_and_gen_docx(
                                     // 类似 List<String>
                                     query_params.push(DocxParamInfo {
                                         name: param.name.clone(),
-                                        data_type: "body.[]".to_string(),
+                                        data_type: if options.array_item_types {
+                                            param_type_name(&type_str, &property_box.items, options)
+                                        } else {
+                                            "body.[]".to_string()
+                                        },
                                         param_type: param_type.clone(),
                                         required: if param.required {
                                             "Y".to_string()
@@ -60,14 +341,49 @@ pub fn parse_swagger_and_gen_docx(
                                             "N".to_string()
                                         },
                                         desc: param.description.clone().unwrap_or("".to_string()),
+                                        example: "".to_string(),
+                                        constraints: constraints_text(
+                                            false,
+                                            &None,
+                                            property_box.unique_items,
+                                        ),
                                     });
+
+                                    // 类似 List<Object>，展开为"body[].field"
+                                    if let Some(SchemaRef::Ref {
+                                        original_ref: Some(original_ref),
+                                        ..
+                                    }) = &property_box.items
+                                    {
+                                        let mut ps = param_by_definitions(
+                                            original_ref,
+                                            &sw.definitions,
+                                            &mut HashSet::new(),
+                                            1,
+                                            options,
+                                        );
+                                        ps.iter_mut().for_each(|item| {
+                                            item.name = field_path(
+                                                "body",
+                                                true,
+                                                &item.name,
+                                                options.flatten_style,
+                                            );
+                                            item.param_type = param_type.clone();
+                                        });
+                                        query_params.extend(ps);
+                                    }
                                 }
                             }
                         }
                     } else {
                         query_params.push(DocxParamInfo {
                             name: param.name.clone(),
-                            data_type: param.param_type.clone().unwrap_or("".to_string()),
+                            data_type: param
+                                .param_type
+                                .as_deref()
+                                .map(|item| param_type_name(item, &param.items, options))
+                                .unwrap_or("".to_string()),
                             param_type: param_type,
                             required: if param.required {
                                 "Y".to_string()
@@ -75,24 +391,37 @@ pub fn parse_swagger_and_gen_docx(
                                 "N".to_string()
                             },
                             desc: param.description.clone().unwrap_or("".to_string()),
+                            example,
+                            constraints: constraints_text(
+                                param.allow_empty_value,
+                                &param.multiple_of,
+                                param.unique_items,
+                            ),
                         });
                     }
                 }
             }
 
-            // 状态码
+            // 状态码，数字状态码升序，default排在最后
             let mut status_codes: Vec<DocxStatusCode> = vec![];
             for ele in &operation.responses {
                 status_codes.push(DocxStatusCode {
                     code: ele.0.clone(),
                     desc: ele.1.description.clone(),
-                    explain: "".to_string(),
+                    explain: status_code_explain(ele.0),
                 });
             }
+            status_codes.sort_by_key(|item| status_code_order(&item.code));
+
+            // 二进制响应（文件下载）不生成返回参数和示例
+            let binary_content_type =
+                binary_response_type(&operation.produces, &operation.responses);
 
             // 返回参数
             let mut return_params: Vec<DocxReturnParamInfo> = vec![];
-            if let Some(response) = &operation.responses.get("200") {
+            if binary_content_type.is_none()
+                && let Some(response) = &documented_response(&operation.responses)
+            {
                 let description = response.description.clone();
                 if let Some(schema) = &response.schema {
                     if let SchemaRef::Ref { ref_, original_ref } = schema {
@@ -100,307 +429,2073 @@ pub fn parse_swagger_and_gen_docx(
                             original_ref.as_ref().unwrap_or(&"".to_string()),
                             &sw.definitions,
                             &mut HashSet::<&String>::new(),
+                            1,
+                            options,
                         );
                         // 在每个参数前面加上"body."
-                        ps.iter_mut()
-                            .for_each(|item| item.name = format!("body.{}", item.name));
+                        ps.iter_mut().for_each(|item| {
+                            item.name = field_path("body", false, &item.name, options.flatten_style)
+                        });
                         return_params.extend(ps);
+                    } else if let SchemaRef::Primitives(property_box) = schema
+                        && let Some(map_type) = map_type_name(&property_box.additional_properties)
+                    {
+                        // 整个响应是map
+                        return_params.push(DocxReturnParamInfo {
+                            name: "body".to_string(),
+                            data_type: map_type,
+                            desc: description.clone(),
+                        });
                     }
                 }
             }
 
-            // 示例
+            // 示例，优先使用响应中声明的示例
+            let declared_example = documented_response(&operation.responses)
+                .and_then(|response| response_example(response, &operation.produces));
+            let xml_type = xml_media_type(&operation.produces);
             let mut example_object = serde_json::Value::Object(Map::new());
-            if let Some(response) = &operation.responses.get("200") {
+            // xml示例的根元素名
+            let mut example_root = "root".to_string();
+            if binary_content_type.is_none()
+                && declared_example.is_none()
+                && let Some(response) = &documented_response(&operation.responses)
+            {
                 let description = response.description.clone();
                 if let Some(schema) = &response.schema {
                     if let SchemaRef::Ref { ref_, original_ref } = schema {
+                        if let Some(name) = ref_model_name(&Some(ref_.clone()), original_ref) {
+                            example_root = name;
+                        }
                         fill_value_by_definitions(
                             original_ref.as_ref().unwrap_or(&"".to_string()),
                             &mut example_object,
                             &sw.definitions,
                             &mut HashSet::<&String>::new(),
+                            1,
+                            options,
+                        );
+                    } else if let SchemaRef::Primitives(property_box) = schema
+                        && map_type_name(&property_box.additional_properties).is_some()
+                    {
+                        example_object = map_example_value(
+                            &property_box.additional_properties,
+                            &sw.definitions,
+                            &mut HashSet::<&String>::new(),
+                            1,
+                            options,
                         );
                     }
                 }
             }
 
             let doc_api_info = DocxApiInfo {
+                number: "".to_string(),
                 name: operation.summary.clone().unwrap_or("".to_string()),
                 desc: operation.summary.clone().unwrap_or("".to_string()),
                 url: url.clone(),
                 method: method,
-                api_type: "".to_string(),
-                return_type: "*/*".to_string(),
+                api_type: operation.consumes.clone().unwrap_or_default().join(", "),
+                return_type: binary_content_type.clone().unwrap_or(
+                    if operation.produces.is_empty() {
+                        "*/*".to_string()
+                    } else {
+                        operation.produces.join(", ")
+                    },
+                ),
                 query_params: query_params,
                 status_codes: status_codes,
                 return_params: return_params,
-                return_params_example: serde_json::to_string(&example_object)
-                    .unwrap_or("".to_string()),
+                return_params_example: match &binary_content_type {
+                    Some(content_type) => format!("二进制文件下载（{content_type}）"),
+                    None => match &declared_example {
+                        Some((_, Value::String(text))) => text.clone(),
+                        Some((_, value)) => serde_json::to_string(value).unwrap_or("".to_string()),
+                        None if options.xml_examples && xml_type.is_some() => {
+                            xml_example(&example_root, &example_object)
+                        }
+                        None => serde_json::to_string(&example_object).unwrap_or("".to_string()),
+                    },
+                },
+                example_media_type: match (&binary_content_type, &declared_example) {
+                    (Some(_), _) => "".to_string(),
+                    (None, Some((content_type, _))) => content_type.clone(),
+                    (None, None) if options.xml_examples => {
+                        xml_type.clone().unwrap_or("application/json".to_string())
+                    }
+                    (None, None) => "application/json".to_string(),
+                },
+                is_binary_response: binary_content_type.is_some(),
+                example_curl,
+                schema_tree: tree_lines.join("\n"),
+                auth,
+                external_docs: external_docs_text(&operation.external_docs),
             };
 
-            // tags
-            for tag in operation.tags {
-                if let Some(vec) = apis.get_mut(&tag) {
-                    vec.push(doc_api_info.clone());
+            match options.group_by {
+                GroupBy::Tag => {
+                    for tag in operation.tags {
+                        if let Some(vec) = apis.get_mut(&tag) {
+                            vec.push(doc_api_info.clone());
+                        }
+                    }
                 }
+                GroupBy::Path => apis.entry(path_group(&url)).or_default().push(doc_api_info),
             }
         }
     }
 
-    let docx_project = DocxProjectInfo {
+    // 按分组过滤，include_tags优先
+    if !options.include_tags.is_empty() {
+        apis.retain(|tag, _| options.include_tags.contains(tag));
+    } else if !options.exclude_tags.is_empty() {
+        apis.retain(|tag, _| !options.exclude_tags.contains(tag));
+    }
+
+    // 分组内的接口按url和请求方式排序，保证每次生成的顺序一致
+    for tag_apis in apis.values_mut() {
+        tag_apis.sort_by(|a, b| (&a.url, &a.method).cmp(&(&b.url, &b.method)));
+    }
+
+    let mut docx_project = DocxProjectInfo {
         name: sw.info.title.clone(),
         apis: apis,
+        tag_descs,
+        tag_numbers: HashMap::new(),
+        generated_at: if options.timestamp {
+            Local::now().to_rfc3339_opts(SecondsFormat::Secs, false)
+        } else {
+            "".to_string()
+        },
+        source: options.source.clone().unwrap_or("".to_string()),
+        version: sw.info.version.clone(),
+        external_docs: external_docs_text(&sw.external_docs),
+        cover: options.cover,
+        logo: options.logo.clone().unwrap_or("".to_string()),
+        models: if options.with_models {
+            model_infos(&sw.definitions)
+        } else {
+            vec![]
+        },
     };
-    println!("{}", serde_json::to_string_pretty(&docx_project)?);
+    if let Some(placeholder) = &options.empty_placeholder {
+        fill_empty_placeholder(&mut docx_project, placeholder);
+    }
+    if options.number_sections {
+        number_sections(&mut docx_project);
+    }
 
-    // 渲染模板
-    let result = render_handlebars(
-        SWAGGER_DOCX_MODEL.to_vec(),
-        &serde_json::to_value(&docx_project)?,
-    )?;
+    Ok(docx_project)
+}
 
-    // 保存
-    std::fs::write(output_file_name, result)?;
+// 解析时发现的问题
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    // 问题所在的接口（如"GET /users"）或定义（如"definitions/User"）
+    pub location: Option<String>,
+}
 
-    return Ok(());
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Severity {
+    // 文档可以生成，但内容可能不完整
+    Warning,
+    // 无法解析，返回的模型为空
+    Error,
 }
 
-// 获得返回属性（嵌套获取）
-fn response_by_definitions<'a>(
-    original_ref: &'a String,
-    definitions: &'a HashMap<String, Definition>,
-    used_name: &mut HashSet<&'a String>,
-) -> Vec<DocxReturnParamInfo> {
-    // 检查是否循环引用
-    if used_name.contains(original_ref) {
-        return vec![];
+impl Diagnostic {
+    fn warning(message: impl Into<String>, location: Option<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            location,
+        }
     }
-    used_name.insert(original_ref);
+}
 
-    let mut ps: Vec<DocxReturnParamInfo> = vec![];
-    if let Some(definition) = definitions.get(original_ref) {
-        if let Definition::Object(scheme) = definition {
-            if let Some(hm) = &scheme.properties {
-                for ele in hm {
-                    let name = ele.0;
-                    let prop = ele.1;
-                    let type_ = &prop.type_;
-                    if let Some(type_value) = type_ {
-                        let data_type = type_value.clone();
-                        if "array" == data_type {
-                            // 列表
-                            if let Some(schema) = &prop.items {
-                                if let SchemaRef::Ref { ref_, original_ref } = schema {
-                                    if let Some(original_ref_value) = original_ref {
-                                        let mut pst = response_by_definitions(
-                                            original_ref_value,
-                                            &definitions,
-                                            &mut used_name.clone(),
-                                        );
-                                        // 在每个参数前面加上"[]."
-                                        pst.iter_mut().for_each(|item| {
-                                            item.name = format!("{}.[].{}", name, item.name)
-                                        });
-                                        ps.extend(pst);
-                                    }
-                                } else if let SchemaRef::Primitives(property_box) = schema {
-                                    // 属性
-                                    let spi = DocxReturnParamInfo {
-                                        // todo 优化
-                                        name: format!(
-                                            "{}.[].{}",
-                                            name,
-                                            property_box.format.clone().unwrap_or("".to_string())
-                                        ),
-                                        data_type: property_box
-                                            .type_
-                                            .clone()
-                                            .unwrap_or("".to_string()),
-                                        desc: prop.description.clone().unwrap_or("".to_string()),
-                                    };
-                                    ps.push(spi);
-                                }
-                            }
-                        } else {
-                            // 属性
-                            let spi = DocxReturnParamInfo {
-                                name: name.clone(),
-                                data_type: data_type,
-                                desc: prop.description.clone().unwrap_or("".to_string()),
-                            };
-                            ps.push(spi);
-                        }
-                    } else if let Some(original_ref_value) = &prop.original_ref {
-                        // 对象
-                        let mut pst = response_by_definitions(
-                            original_ref_value,
-                            &definitions,
-                            &mut used_name.clone(),
-                        );
-                        // 在每个参数前面加上"."
-                        pst.iter_mut()
-                            .for_each(|item| item.name = format!("{}.{}", name, item.name));
-                        ps.extend(pst);
-                    }
-                }
-            }
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "警告",
+            Severity::Error => "错误",
+        };
+        match &self.location {
+            Some(location) => write!(f, "[{severity}] {location}: {}", self.message),
+            None => write!(f, "[{severity}] {}", self.message),
         }
     }
-
-    return ps;
 }
 
-// 属性填充Value
-fn fill_value_by_definitions<'a>(
-    original_ref: &'a String,
-    value: &mut Value,
-    definitions: &'a HashMap<String, Definition>,
-    used_name: &mut HashSet<&'a String>,
-) {
-    // 检查是否循环引用
-    if used_name.contains(original_ref) {
-        return;
+// 解析swagger并收集问题，不会失败：无法解析时返回空的模型和一个Error
+pub fn parse_swagger_with_diagnostics(
+    swagger_bytes: &[u8],
+    options: &SwaggerOptions,
+) -> (DocxProjectInfo, Vec<Diagnostic>) {
+    let project = match parse_swagger(swagger_bytes, options) {
+        Ok(project) => project,
+        Err(e) => {
+            let diagnostic = Diagnostic {
+                severity: Severity::Error,
+                message: format!("无法解析swagger, {e}"),
+                location: None,
+            };
+            return (DocxProjectInfo::default(), vec![diagnostic]);
+        }
+    };
+
+    let mut diagnostics = vec![];
+    let sw: Value = serde_json::from_slice(swagger_bytes).unwrap_or(Value::Null);
+    let definitions = sw.get("definitions").cloned().unwrap_or(Value::Null);
+    let shared_parameters = sw.get("parameters").cloned().unwrap_or(Value::Null);
+    let paths_value = sw.get("paths").and_then(|item| item.as_object());
+    let tags = match options.group_by {
+        GroupBy::Tag => sw
+            .get("tags")
+            .and_then(|item| item.as_array())
+            .map(|item| {
+                item.iter()
+                    .filter_map(|tag| tag.get("name").and_then(|name| name.as_str()))
+                    .map(|name| name.to_string())
+                    .collect::<HashSet<String>>()
+            })
+            .unwrap_or_default(),
+        GroupBy::Path => paths_value
+            .map(|item| item.keys().map(|url| path_group(url)).collect())
+            .unwrap_or_default(),
+    };
+
+    // 过滤的分组不存在时提示，可能是分组名写错了
+    let filter_tags = if options.include_tags.is_empty() {
+        &options.exclude_tags
+    } else {
+        &options.include_tags
+    };
+    for tag in filter_tags
+        .iter()
+        .filter(|tag| !tags.contains(tag.as_str()))
+    {
+        diagnostics.push(Diagnostic::warning(
+            format!("要过滤的分组{tag}不存在"),
+            None,
+        ));
     }
-    used_name.insert(original_ref);
 
-    if let Some(definition) = definitions.get(original_ref) {
-        if let Definition::Object(scheme) = definition {
-            if let Some(hm) = &scheme.properties {
-                for ele in hm {
-                    let name = ele.0;
-                    let prop = ele.1;
-                    let type_ = &prop.type_;
-                    if let Some(type_value) = type_ {
-                        let data_type = type_value.clone();
-                        if "array" == data_type {
-                            // 列表
-                            if let Some(schema) = &prop.items {
-                                if let SchemaRef::Ref { ref_, original_ref } = schema {
-                                    if let Some(original_ref_value) = original_ref {
-                                        let mut value_item = Value::Object(Map::new());
-                                        fill_value_by_definitions(
-                                            original_ref_value,
-                                            &mut value_item,
-                                            &definitions,
-                                            &mut used_name.clone(),
-                                        );
-                                        value.as_object_mut().unwrap().insert(
-                                            name.to_string(),
-                                            Value::Array(vec![value_item]),
-                                        );
-                                    }
-                                } else if let SchemaRef::Primitives(property_box) = schema {
-                                    // 属性
-                                    // todo 空数组
-                                    value
-                                        .as_object_mut()
-                                        .unwrap()
-                                        .insert(name.to_string(), Value::Array(vec![]));
-                                }
-                            }
-                        } else {
-                            // 属性
-                            value.as_object_mut().unwrap().insert(
-                                name.to_string(),
-                                gen_example_value(&name, &data_type, &prop.example),
-                            );
-                        }
-                    } else if let Some(original_ref_value) = &prop.original_ref {
-                        // 对象
-                        let mut value_item = Value::Object(Map::new());
-                        fill_value_by_definitions(
-                            original_ref_value,
-                            &mut value_item,
-                            &definitions,
-                            &mut used_name.clone(),
-                        );
-                        value
-                            .as_object_mut()
-                            .unwrap()
-                            .insert(name.to_string(), value_item);
-                    }
+    let mut paths = paths_value
+        .map(|item| item.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    paths.sort_by_key(|(url, _)| url.as_str());
+    for (url, methods) in paths {
+        let Some(methods) = methods.as_object() else {
+            continue;
+        };
+        for (method, operation) in methods {
+            let location = Some(format!("{} {url}", method.to_uppercase()));
+
+            // 只有tags中声明的分组才会生成，按路径分组时不检查
+            if options.group_by == GroupBy::Tag {
+                let operation_tags = operation
+                    .get("tags")
+                    .and_then(|item| item.as_array())
+                    .map(|item| {
+                        item.iter()
+                            .filter_map(|tag| tag.as_str())
+                            .collect::<Vec<&str>>()
+                    })
+                    .unwrap_or_default();
+                if operation_tags.is_empty() {
+                    diagnostics.push(Diagnostic::warning(
+                        "接口没有分组，不会出现在文档中",
+                        location.clone(),
+                    ));
+                }
+                for tag in operation_tags.iter().filter(|tag| !tags.contains(**tag)) {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("分组{tag}没有在tags中声明，接口不会出现在该分组"),
+                        location.clone(),
+                    ));
+                }
+            }
+
+            // 找不到的共享参数会被忽略
+            for param in operation
+                .get("parameters")
+                .and_then(|item| item.as_array())
+                .into_iter()
+                .flatten()
+            {
+                if let Some(ref_) = param.get("$ref").and_then(|item| item.as_str())
+                    && ref_
+                        .strip_prefix("#/parameters/")
+                        .and_then(|name| shared_parameters.get(name))
+                        .is_none()
+                {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("参数引用{ref_}不存在，已忽略"),
+                        location.clone(),
+                    ));
                 }
             }
+
+            diagnostics.extend(missing_definition_refs(operation, &definitions, location));
         }
     }
+
+    let mut names = definitions
+        .as_object()
+        .map(|item| item.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    names.sort_by_key(|(name, _)| name.as_str());
+    for (name, definition) in names {
+        diagnostics.extend(missing_definition_refs(
+            definition,
+            &definitions,
+            Some(format!("definitions/{name}")),
+        ));
+    }
+
+    if project.endpoint_count() == 0 {
+        let reason = match &options.since {
+            Some(since) => format!("所有接口都被--since {since}过滤"),
+            None if !filter_tags.is_empty() => "所有分组都被过滤".to_string(),
+            None => "paths为空或接口都没有生成".to_string(),
+        };
+        diagnostics.push(Diagnostic::warning(
+            format!("swagger中没有可生成的接口：{reason}"),
+            None,
+        ));
+    }
+
+    (project, diagnostics)
 }
 
-fn param_by_definitions(
-    original_ref: &String,
-    definitions: &HashMap<String, Definition>,
-) -> Vec<DocxParamInfo> {
-    let mut ps: Vec<DocxParamInfo> = vec![];
-    if let Some(definition) = definitions.get(original_ref) {
-        if let Definition::Object(scheme) = definition {
-            let reqwest_long = vec![];
-            let require = scheme.required.as_ref().unwrap_or(&reqwest_long);
-            if let Some(hm) = &scheme.properties {
-                for ele in hm {
-                    let name = ele.0;
-                    let prop = ele.1;
-                    let type_ = &prop.type_;
-                    if let Some(type_value) = type_ {
-                        let spi = DocxParamInfo {
-                            name: name.clone(),
-                            data_type: type_value.clone(),
-                            param_type: "".to_string(),
-                            required: if require.contains(name) {
-                                "Y".to_string()
-                            } else {
-                                "N".to_string()
-                            },
-                            desc: prop.description.clone().unwrap_or("".to_string()),
-                        };
-                        ps.push(spi);
+// 引用了不存在的定义，每个引用只报告一次
+fn missing_definition_refs(
+    value: &Value,
+    definitions: &Value,
+    location: Option<String>,
+) -> Vec<Diagnostic> {
+    let mut refs = vec![];
+    collect_definition_refs(value, &mut refs);
+    let mut missing = refs
+        .into_iter()
+        .filter(|name| definitions.get(name).is_none())
+        .collect::<Vec<String>>();
+    missing.sort();
+    missing.dedup();
+    missing
+        .into_iter()
+        .map(|name| {
+            Diagnostic::warning(
+                format!("引用的定义{name}不存在，相关字段不会展开"),
+                location.clone(),
+            )
+        })
+        .collect()
+}
+
+// 接口没有设置produces、consumes时使用全局的设置
+fn inherit_content_types(sw: &mut SwaggerDocument) {
+    for operation in sw
+        .paths
+        .values_mut()
+        .flat_map(|methods| methods.values_mut())
+    {
+        if operation.produces.is_empty() {
+            operation.produces = sw.produces.clone();
+        }
+        if operation
+            .consumes
+            .as_ref()
+            .is_none_or(|item| item.is_empty())
+            && !sw.consumes.is_empty()
+        {
+            operation.consumes = Some(sw.consumes.clone());
+        }
+    }
+}
+
+// 把接口中"#/parameters/名称"形式的参数引用替换为根节点parameters中的定义
+// 引用旁边写的其他字段（如description、required）覆盖共享定义中的同名字段，找不到定义的引用会被丢弃
+fn resolve_parameter_refs(sw: &mut Value) {
+    let shared = sw.get("parameters").cloned().unwrap_or(Value::Null);
+    let Some(paths) = sw.get_mut("paths").and_then(|item| item.as_object_mut()) else {
+        return;
+    };
+    for operation in paths
+        .values_mut()
+        .filter_map(|item| item.as_object_mut())
+        .flat_map(|methods| methods.values_mut())
+    {
+        let Some(params) = operation
+            .get_mut("parameters")
+            .and_then(|item| item.as_array_mut())
+        else {
+            continue;
+        };
+        let resolved = params
+            .iter()
+            .filter_map(|param| {
+                let Some(ref_) = param.get("$ref").and_then(|item| item.as_str()) else {
+                    return Some(param.clone());
+                };
+                let name = ref_.strip_prefix("#/parameters/")?;
+                let mut merged = shared.get(name)?.as_object()?.clone();
+                for (key, value) in param.as_object()? {
+                    if key != "$ref" {
+                        merged.insert(key.clone(), value.clone());
                     }
                 }
+                Some(Value::Object(merged))
+            })
+            .collect();
+        *params = resolved;
+    }
+}
+
+// 按渲染顺序编号：分组按名称排序（与模板中遍历apis的顺序一致），接口按分组内的顺序
+fn number_sections(project: &mut DocxProjectInfo) {
+    let mut tags = project.apis.keys().cloned().collect::<Vec<String>>();
+    tags.sort();
+    for (tag_index, tag) in tags.iter().enumerate() {
+        let chapter = tag_index + 1;
+        project.tag_numbers.insert(tag.clone(), chapter.to_string());
+        if let Some(tag_apis) = project.apis.get_mut(tag) {
+            for (api_index, api) in tag_apis.iter_mut().enumerate() {
+                api.number = format!("{chapter}.{}", api_index + 1);
             }
         }
     }
+}
 
-    return ps;
+// 把空的说明、数据类型和认证替换为占位符，避免渲染出空白单元格
+fn fill_empty_placeholder(project: &mut DocxProjectInfo, placeholder: &str) {
+    let fill = |text: &mut String| {
+        if text.trim().is_empty() {
+            *text = placeholder.to_string();
+        }
+    };
+    let fill_param = |param: &mut DocxParamInfo| {
+        fill(&mut param.data_type);
+        fill(&mut param.desc);
+    };
+
+    for api in project.apis.values_mut().flatten() {
+        fill(&mut api.desc);
+        fill(&mut api.auth);
+        api.query_params.iter_mut().for_each(fill_param);
+        for return_param in api.return_params.iter_mut() {
+            fill(&mut return_param.data_type);
+            fill(&mut return_param.desc);
+        }
+        for status_code in api.status_codes.iter_mut() {
+            fill(&mut status_code.desc);
+        }
+    }
+    for model in project.models.iter_mut() {
+        fill(&mut model.desc);
+        model.fields.iter_mut().for_each(fill_param);
+    }
 }
 
-// 生成测试数据
-fn gen_example_value(name: &String, value_type: &String, example: &Option<Value>) -> Value {
-    // 有示例数据则使用示例数据
-    if let Some(example_value) = example {
-        // return Value::String(example_value.clone());
-        return example_value.clone();
+// 数据模型附录，按名称排序，每个模型只列出自己的字段，引用的模型单独列出
+fn model_infos(definitions: &HashMap<String, Definition>) -> Vec<DocxModelInfo> {
+    let mut models: Vec<DocxModelInfo> = vec![];
+    for (name, definition) in definitions {
+        let Definition::Object(schema) = definition else {
+            continue;
+        };
+        let required = schema.required.clone().unwrap_or_default();
+        let mut fields: Vec<DocxParamInfo> = vec![];
+        for (field, prop) in schema.properties.iter().flatten() {
+            fields.push(
+                DocxParamInfo::new(field, property_type_name(prop))
+                    .required(required.contains(field))
+                    .desc(prop.description.clone().unwrap_or("".to_string()))
+                    .constraints(constraints_text(
+                        false,
+                        &prop.multiple_of,
+                        prop.unique_items,
+                    )),
+            );
+        }
+        if let Some(discriminator) = discriminator(name, definitions) {
+            for item in fields
+                .iter_mut()
+                .filter(|item| &item.name == discriminator.field)
+            {
+                item.desc = discriminator.desc(&item.desc, name);
+            }
+        }
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+        models.push(DocxModelInfo {
+            name: name.clone(),
+            desc: schema
+                .description
+                .clone()
+                .or(schema.title.clone())
+                .unwrap_or("".to_string()),
+            fields,
+        });
     }
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    models
+}
 
-    if "integer" == value_type {
-        return Value::Number(Number::from(1u32));
+// 字段的类型名，引用显示为模型名，数组显示为array<元素类型>
+fn property_type_name(prop: &Property) -> String {
+    if let Some(name) = ref_model_name(&prop.ref_, &prop.original_ref) {
+        return name;
     }
-    if "boolean" == value_type {
-        return Value::Bool(false);
+    if let Some(map_type) = map_type_name(&prop.additional_properties) {
+        return map_type;
     }
-    if "string" == value_type {
-        // 日期
-        if name.to_lowercase().contains("time") || name.to_lowercase().contains("date") {
-            return Value::String("2025-10-13 20:26:09".to_string());
+    match prop.type_.as_deref() {
+        Some("array") => format!("array<{}>", items_type_name(&prop.items)),
+        Some(type_) => leaf_type_name(prop, type_),
+        None => "object".to_string(),
+    }
+}
+
+// 数组元素的类型名
+fn items_type_name(items: &Option<SchemaRef>) -> String {
+    match items {
+        Some(SchemaRef::Ref { ref_, original_ref }) => {
+            ref_model_name(&Some(ref_.clone()), original_ref).unwrap_or("object".to_string())
         }
-        return Value::String("string".to_string());
+        Some(SchemaRef::Primitives(item)) => property_type_name(item),
+        _ => "object".to_string(),
     }
+}
 
-    return Value::Null;
+// 参数的类型名，开启array_item_types时数组显示为array<元素类型>
+fn param_type_name(
+    type_value: &str,
+    items: &Option<SchemaRef>,
+    options: &SwaggerOptions,
+) -> String {
+    if options.array_item_types && type_value == "array" {
+        return format!("array<{}>", items_type_name(items));
+    }
+    type_value.to_string()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SwaggerDocument {
-    pub swagger: String,
-    pub info: Info,
-    pub host: String,
-    pub basePath: Option<String>,
-    pub tags: Vec<Tag>,
-    pub paths: HashMap<String, HashMap<String, Operation>>,
-    pub securityDefinitions: HashMap<String, SecurityDefinition>,
-    pub definitions: HashMap<String, Definition>,
+// 字段的类型名，没有properties、$ref和additionalProperties的object显示为任意对象
+fn leaf_type_name(prop: &Property, type_value: &str) -> String {
+    if type_value == "object"
+        && prop.properties.is_none()
+        && prop.ref_.is_none()
+        && prop.additional_properties.is_none()
+    {
+        return FREE_FORM_OBJECT.to_string();
+    }
+    type_value.to_string()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+// map字段的类型名，显示为map<string, 值类型>，不是map时返回None
+fn map_type_name(additional: &Option<AdditionalProperties>) -> Option<String> {
+    let value_type = match additional.as_ref()? {
+        AdditionalProperties::Any(false) => return None,
+        AdditionalProperties::Any(true) => "object".to_string(),
+        AdditionalProperties::Schema(schema) => match schema.as_ref() {
+            SchemaRef::Ref { ref_, original_ref } => {
+                ref_model_name(&Some(ref_.clone()), original_ref).unwrap_or("object".to_string())
+            }
+            SchemaRef::Primitives(item) => property_type_name(item),
+            SchemaRef::Object(_) => "object".to_string(),
+        },
+    };
+    Some(format!("map<string, {value_type}>"))
+}
+
+// map字段的示例，只有一个key的对象
+fn map_example_value<'a>(
+    additional: &'a Option<AdditionalProperties>,
+    definitions: &'a HashMap<String, Definition>,
+    used_name: &mut HashSet<&'a String>,
+    depth: usize,
+    options: &SwaggerOptions,
+) -> Value {
+    let key = "key".to_string();
+    let value = match additional {
+        Some(AdditionalProperties::Schema(schema)) => match schema.as_ref() {
+            SchemaRef::Ref {
+                original_ref: Some(original_ref),
+                ..
+            } => {
+                let mut value_item = Value::Object(Map::new());
+                fill_value_by_definitions(
+                    original_ref,
+                    &mut value_item,
+                    definitions,
+                    used_name,
+                    depth,
+                    options,
+                );
+                value_item
+            }
+            SchemaRef::Primitives(item) if item.type_.as_deref() != Some("array") => {
+                gen_example_value(
+                    &key,
+                    &item.type_.clone().unwrap_or("string".to_string()),
+                    &item.example,
+                    &item.enum_,
+                    options,
+                )
+            }
+            SchemaRef::Primitives(_) => Value::Array(vec![]),
+            _ => Value::Object(Map::new()),
+        },
+        _ => Value::Object(Map::new()),
+    };
+    let mut map = Map::new();
+    map.insert(key, value);
+    Value::Object(map)
+}
+
+// 引用的模型名，优先使用originalRef
+fn ref_model_name(ref_: &Option<String>, original_ref: &Option<String>) -> Option<String> {
+    original_ref.clone().or(ref_
+        .as_ref()
+        .map(|item| item.trim_start_matches("#/definitions/").to_string()))
+}
+
+// 接口清单导出为csv，每个接口一行，分组和接口的顺序与文档相同
+pub fn project_to_csv(project: &DocxProjectInfo) -> String {
+    let mut lines = vec![
+        [
+            "tag",
+            "method",
+            "url",
+            "name",
+            "auth",
+            "param_count",
+            "status_codes",
+        ]
+        .join(","),
+    ];
+    let mut tags = project.apis.keys().collect::<Vec<&String>>();
+    tags.sort();
+    for tag in tags {
+        for api in &project.apis[tag] {
+            let status_codes = api
+                .status_codes
+                .iter()
+                .map(|item| item.code.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            let fields = [
+                tag.as_str(),
+                &api.method,
+                &api.url,
+                &api.name,
+                &api.auth,
+                &api.query_params.len().to_string(),
+                &status_codes,
+            ];
+            lines.push(
+                fields
+                    .iter()
+                    .map(|item| csv_field(item))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+    }
+    lines.join("\r\n") + "\r\n"
+}
+
+// 包含逗号、引号或换行的字段用引号包裹，引号写两次
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// 把swagger的definitions导出为独立的JSON Schema，返回(定义名, schema)
+// 引用的其他定义复制到schema的definitions中，$ref保持"#/definitions/名称"，循环引用不会展开
+pub fn export_json_schemas(
+    swagger_bytes: &[u8],
+) -> Result<Vec<(String, Value)>, Box<dyn std::error::Error>> {
+    let sw: Value = serde_json::from_slice(swagger_bytes)?;
+    let mut definitions = sw
+        .get("definitions")
+        .and_then(|item| item.as_object())
+        .cloned()
+        .unwrap_or_default();
+    definitions.values_mut().for_each(remove_original_ref);
+
+    let mut schemas = vec![];
+    for (name, definition) in &definitions {
+        // 递归收集引用的定义
+        let mut used: Vec<String> = vec![];
+        let mut pending = vec![definition];
+        while let Some(value) = pending.pop() {
+            let mut refs = vec![];
+            collect_definition_refs(value, &mut refs);
+            for ref_name in refs {
+                if !used.contains(&ref_name)
+                    && let Some(ref_definition) = definitions.get(&ref_name)
+                {
+                    used.push(ref_name);
+                    pending.push(ref_definition);
+                }
+            }
+        }
+
+        let mut schema = Map::new();
+        schema.insert(
+            "$schema".to_string(),
+            Value::String("http://json-schema.org/draft-04/schema#".to_string()),
+        );
+        schema.insert("title".to_string(), Value::String(name.clone()));
+        if let Some(definition) = definition.as_object() {
+            schema.extend(definition.clone());
+        }
+        if !used.is_empty() {
+            used.sort();
+            schema.insert(
+                "definitions".to_string(),
+                Value::Object(
+                    used.into_iter()
+                        .map(|ref_name| {
+                            let ref_definition = definitions[&ref_name].clone();
+                            (ref_name, ref_definition)
+                        })
+                        .collect(),
+                ),
+            );
+        }
+        schemas.push((name.clone(), Value::Object(schema)));
+    }
+
+    Ok(schemas)
+}
+
+// 收集"#/definitions/名称"形式的引用
+fn collect_definition_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(ref_)) = map.get("$ref")
+                && let Some(name) = ref_.strip_prefix("#/definitions/")
+            {
+                // json pointer的转义
+                refs.push(name.replace("~1", "/").replace("~0", "~"));
+            }
+            map.values()
+                .for_each(|item| collect_definition_refs(item, refs));
+        }
+        Value::Array(arr) => arr
+            .iter()
+            .for_each(|item| collect_definition_refs(item, refs)),
+        _ => {}
+    }
+}
+
+// 移除springfox生成的originalRef，它不是JSON Schema的关键字
+fn remove_original_ref(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("originalRef");
+            map.values_mut().for_each(remove_original_ref);
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(remove_original_ref),
+        _ => {}
+    }
+}
+
+// 使用内置模板渲染接口文档，可用于非swagger来源构建的DocxProjectInfo
+pub fn render_swagger_docx(
+    project: &DocxProjectInfo,
+    lang: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut value = serde_json::to_value(project)?;
+    if let Some(map) = value.as_object_mut() {
+        for (key, item) in map.iter_mut() {
+            // logo是base64图片，由img helper解码，不做替换
+            if key != "logo" {
+                protect_chars(item);
+            }
+        }
+    }
+    let result = render_handlebars(swagger_docx_model(lang).to_vec(), &value)?;
+    restore_protected_chars(result)
+}
+
+// docx-handlebars删除表格行时会丢掉行内的实体引用（如&lt;、&amp;），xml示例、"x<y"等文本会缺字符
+// 渲染前把会被转义的字符替换为私用区字符，渲染后在xml中换回对应的实体
+// 换行换为w:br，schema_tree等多行文本在word中按行显示
+const PROTECTED_CHARS: [(char, char, &str); 8] = [
+    ('&', '\u{E000}', "&amp;"),
+    ('<', '\u{E001}', "&lt;"),
+    ('>', '\u{E002}', "&gt;"),
+    ('"', '\u{E003}', "&quot;"),
+    ('\'', '\u{E004}', "&apos;"),
+    ('=', '\u{E005}', "="),
+    ('`', '\u{E006}', "`"),
+    (
+        '\n',
+        '\u{E007}',
+        r#"</w:t><w:br/><w:t xml:space="preserve">"#,
+    ),
+];
+
+fn protect_chars(value: &mut Value) {
+    match value {
+        Value::String(text) => {
+            *text = text
+                .chars()
+                .map(|c| {
+                    PROTECTED_CHARS
+                        .iter()
+                        .find(|item| item.0 == c)
+                        .map_or(c, |item| item.1)
+                })
+                .collect();
+        }
+        Value::Array(items) => items.iter_mut().for_each(protect_chars),
+        Value::Object(map) => map.values_mut().for_each(protect_chars),
+        _ => {}
+    }
+}
+
+fn restore_protected_chars(docx_bytes: Vec<u8>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx_bytes))?;
+    let mut buf = std::io::Cursor::new(vec![]);
+    let mut writer = zip::ZipWriter::new(&mut buf);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        let name = file.name().to_string();
+        let mut content = vec![];
+        std::io::Read::read_to_end(&mut file, &mut content)?;
+        if name.starts_with("word/") && name.ends_with(".xml") {
+            let mut text = String::from_utf8(content)?;
+            for (_, placeholder, entity) in PROTECTED_CHARS {
+                if text.contains(placeholder) {
+                    text = text.replace(placeholder, entity);
+                }
+            }
+            content = text.into_bytes();
+        }
+        writer.start_file(name, options)?;
+        std::io::Write::write_all(&mut writer, &content)?;
+    }
+    writer.finish()?;
+    drop(writer);
+    Ok(buf.into_inner())
+}
+
+fn escape_xml_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// 常见的html标签，其他"<...>"（如List<String>）不做处理
+const HTML_TAGS: &[&str] = &[
+    "a", "b", "br", "code", "div", "em", "font", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i",
+    "li", "ol", "p", "pre", "span", "strong", "sub", "sup", "table", "tbody", "td", "th", "thead",
+    "tr", "u", "ul",
+];
+
+// 移除swagger中summary和description的html标签
+fn strip_html_descriptions(sw: &mut SwaggerDocument) {
+    fn strip_option(text: &mut Option<String>) {
+        if let Some(value) = text {
+            *value = strip_html(value);
+        }
+    }
+    fn strip_property(prop: &mut Property) {
+        strip_option(&mut prop.description);
+        if let Some(SchemaRef::Primitives(item)) = &mut prop.items {
+            strip_property(item);
+        }
+    }
+
+    for tag in sw.tags.iter_mut() {
+        strip_option(&mut tag.description);
+    }
+    for operations in sw.paths.values_mut() {
+        for operation in operations.values_mut() {
+            strip_option(&mut operation.summary);
+            for param in operation.parameters.iter_mut().flatten() {
+                strip_option(&mut param.description);
+            }
+            for response in operation.responses.values_mut() {
+                response.description = strip_html(&response.description);
+            }
+        }
+    }
+    for definition in sw.definitions.values_mut() {
+        if let Definition::Object(schema) = definition {
+            strip_option(&mut schema.description);
+            for prop in schema
+                .properties
+                .iter_mut()
+                .flat_map(|item| item.values_mut())
+            {
+                strip_property(prop);
+            }
+        }
+    }
+}
+
+// 移除html标签，<br>和段落结束转为换行
+fn strip_html(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let tag = tail.find('>').map(|end| &tail[..=end]);
+        match tag.and_then(|tag| html_tag_name(tag).map(|name| (tag, name))) {
+            Some((tag, name)) => {
+                let closing = tag.starts_with("</");
+                if name == "br" || (closing && ["p", "div", "li"].contains(&name.as_str())) {
+                    result.push('\n');
+                }
+                rest = &tail[tag.len()..];
+            }
+            None => {
+                result.push('<');
+                rest = &tail[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    if result == text {
+        return result;
+    }
+    result.trim().to_string()
+}
+
+// 标签名，不是常见的html标签时返回None
+fn html_tag_name(tag: &str) -> Option<String> {
+    let inner = tag
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .trim_start_matches('/')
+        .trim_end_matches('/');
+    // "<"后必须紧跟标签名，如"a < b"不是标签
+    if inner.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let name = inner.split_whitespace().next()?.to_lowercase();
+    HTML_TAGS.contains(&name.as_str()).then_some(name)
+}
+
+// 生成curl调用示例，路径参数和查询参数使用示例值替换
+fn build_example_curl(
+    method: &str,
+    url: &str,
+    operation: &Operation,
+    definitions: &HashMap<String, Definition>,
+    security_definitions: &HashMap<String, SecurityDefinition>,
+    options: &SwaggerOptions,
+) -> String {
+    let example_of = |param: &Parameter| param_example(param, options);
+
+    let mut url = url.to_string();
+    let mut query: Vec<String> = vec![];
+    let mut headers: Vec<String> = vec![];
+    let mut forms: Vec<String> = vec![];
+    let mut body: Option<String> = None;
+    for param in operation.parameters.iter().flatten() {
+        match param.in_.as_str() {
+            "path" => url = url.replace(&format!("{{{}}}", param.name), &example_of(param)),
+            "query" => query.push(format!("{}={}", param.name, example_of(param))),
+            "header" => headers.push(format!("{}: {}", param.name, example_of(param))),
+            "formData" => forms.push(format!("{}={}", param.name, example_of(param))),
+            "body" => {
+                let mut value = Value::Object(Map::new());
+                match &param.schema {
+                    Some(SchemaRef::Ref {
+                        original_ref: Some(original_ref),
+                        ..
+                    }) => fill_value_by_definitions(
+                        original_ref,
+                        &mut value,
+                        definitions,
+                        &mut HashSet::new(),
+                        1,
+                        options,
+                    ),
+                    Some(SchemaRef::Primitives(property))
+                        if property.type_.as_deref() == Some("array") =>
+                    {
+                        // 数组请求体，示例包含一个元素
+                        value = match &property.items {
+                            Some(SchemaRef::Ref {
+                                original_ref: Some(original_ref),
+                                ..
+                            }) => {
+                                let mut item = Value::Object(Map::new());
+                                fill_value_by_definitions(
+                                    original_ref,
+                                    &mut item,
+                                    definitions,
+                                    &mut HashSet::new(),
+                                    1,
+                                    options,
+                                );
+                                Value::Array(vec![item])
+                            }
+                            Some(SchemaRef::Primitives(item)) => {
+                                Value::Array(vec![gen_example_value(
+                                    &param.name,
+                                    &item.type_.clone().unwrap_or("string".to_string()),
+                                    &item.example,
+                                    &item.enum_,
+                                    options,
+                                )])
+                            }
+                            _ => Value::Array(vec![]),
+                        };
+                    }
+                    _ => {}
+                }
+                body = Some(value.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    // 认证信息
+    for requirement in operation.security.iter().flatten() {
+        for name in requirement.keys() {
+            if let Some(definition) = security_definitions.get(name) {
+                match (definition.type_.as_str(), definition.in_.as_str()) {
+                    ("oauth2", _) => {
+                        headers.push("Authorization: Bearer <access_token>".to_string())
+                    }
+                    ("basic", _) => headers.push("Authorization: Basic <credentials>".to_string()),
+                    (_, "header") => {
+                        headers.push(format!("{}: <{}>", definition.name, definition.name))
+                    }
+                    (_, "query") => {
+                        query.push(format!("{}=<{}>", definition.name, definition.name))
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if !query.is_empty() {
+        url = format!("{url}?{}", query.join("&"));
+    }
+    let mut curl = format!("curl -X {} {}", method.to_uppercase(), shell_quote(&url));
+    for header in &headers {
+        curl.push_str(&format!(" -H {}", shell_quote(header)));
+    }
+    for form in &forms {
+        curl.push_str(&format!(" -F {}", shell_quote(form)));
+    }
+    if let Some(body) = body {
+        let content_type = operation
+            .consumes
+            .as_ref()
+            .and_then(|item| item.first())
+            .map(|item| item.as_str())
+            .unwrap_or("application/json");
+        curl.push_str(&format!(
+            " -H {} -d {}",
+            shell_quote(&format!("Content-Type: {content_type}")),
+            shell_quote(&body)
+        ));
+    }
+    curl
+}
+
+// 接口的认证说明，多个可选方案之间用"；"分隔，同时需要的认证之间用" + "连接
+fn security_desc(
+    operation: &Operation,
+    security_definitions: &HashMap<String, SecurityDefinition>,
+) -> String {
+    operation
+        .security
+        .iter()
+        .flatten()
+        .map(|requirement| {
+            let mut names = requirement.keys().collect::<Vec<&String>>();
+            names.sort();
+            names
+                .into_iter()
+                .map(|name| {
+                    let Some(definition) = security_definitions.get(name) else {
+                        return name.clone();
+                    };
+                    match definition.type_.as_str() {
+                        "oauth2" => {
+                            let mut parts = vec![format!(
+                                "OAuth2 {}",
+                                definition.flow.clone().unwrap_or("".to_string())
+                            )];
+                            if let Some(url) = &definition.authorization_url {
+                                parts.push(format!("授权地址: {url}"));
+                            }
+                            if let Some(url) = &definition.token_url {
+                                parts.push(format!("令牌地址: {url}"));
+                            }
+                            // 只列出接口要求的scope，附带定义中的说明
+                            let scopes = requirement[name]
+                                .iter()
+                                .map(|scope| {
+                                    match definition
+                                        .scopes
+                                        .as_ref()
+                                        .and_then(|item| item.get(scope))
+                                        .filter(|item| !item.is_empty())
+                                    {
+                                        Some(desc) => format!("{scope}（{desc}）"),
+                                        None => scope.clone(),
+                                    }
+                                })
+                                .collect::<Vec<String>>();
+                            if !scopes.is_empty() {
+                                parts.push(format!("scopes: {}", scopes.join(", ")));
+                            }
+                            format!("{name}（{}）", parts.join("，"))
+                        }
+                        "apiKey" => format!(
+                            "{name}（apiKey，{}参数 {}）",
+                            definition.in_, definition.name
+                        ),
+                        other => format!("{name}（{other}）"),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" + ")
+        })
+        .collect::<Vec<String>>()
+        .join("；")
+}
+
+// 使用单引号包裹shell参数
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+// 用于生成返回参数和示例的响应，优先200，没有时使用default
+fn documented_response(responses: &HashMap<String, Response>) -> Option<&Response> {
+    responses
+        .get("200")
+        .or_else(|| {
+            responses
+                .iter()
+                .find(|(code, _)| status_code_range(code) == Some(2))
+                .map(|(_, response)| response)
+        })
+        .or(responses.get("default"))
+}
+
+// 范围状态码（如2XX）的类别，不是范围时返回None
+fn status_code_range(code: &str) -> Option<u32> {
+    let mut chars = code.chars();
+    let class = chars
+        .next()?
+        .to_digit(10)
+        .filter(|item| (1..=5).contains(item))?;
+    let rest = chars.as_str();
+    if rest.eq_ignore_ascii_case("XX") {
+        Some(class)
+    } else {
+        None
+    }
+}
+
+// 状态码的说明，default和范围状态码按类别说明
+fn status_code_explain(code: &str) -> String {
+    if code == "default" {
+        return "默认/其他".to_string();
+    }
+    match status_code_range(code) {
+        Some(1) => "信息",
+        Some(2) => "成功",
+        Some(3) => "重定向",
+        Some(4) => "客户端错误",
+        Some(5) => "服务端错误",
+        _ => "",
+    }
+    .to_string()
+}
+
+// 响应中声明的示例，按produces的顺序查找，其次使用json类型的示例
+fn response_example(response: &Response, produces: &[String]) -> Option<(String, Value)> {
+    let examples = response.examples.as_ref()?;
+    produces
+        .iter()
+        .find_map(|content_type| examples.get_key_value(content_type))
+        .or_else(|| {
+            examples
+                .iter()
+                .find(|(content_type, _)| content_type.contains("json"))
+        })
+        .map(|(content_type, value)| (content_type.clone(), value.clone()))
+}
+
+// produces中只有xml时返回xml的content type
+fn xml_media_type(produces: &[String]) -> Option<String> {
+    if produces
+        .iter()
+        .any(|item| item.contains("json") || item.as_str() == "*/*")
+    {
+        return None;
+    }
+    produces.iter().find(|item| item.contains("xml")).cloned()
+}
+
+// 把json示例转为xml，字段名作为元素名，数组的每个元素重复使用字段名
+// 与json示例一样不换行
+fn xml_example(root: &str, value: &Value) -> String {
+    fn write_element(name: &str, value: &Value, out: &mut String) {
+        match value {
+            Value::Object(map) => {
+                out.push_str(&format!("<{name}>"));
+                for (key, item) in map {
+                    write_element(key, item, out);
+                }
+                out.push_str(&format!("</{name}>"));
+            }
+            Value::Array(items) => {
+                for item in items {
+                    write_element(name, item, out);
+                }
+            }
+            Value::Null => out.push_str(&format!("<{name}/>")),
+            Value::String(text) => {
+                out.push_str(&format!("<{name}>{}</{name}>", escape_xml_text(text)))
+            }
+            _ => out.push_str(&format!("<{name}>{value}</{name}>")),
+        }
+    }
+
+    let mut out = r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string();
+    write_element(root, value, &mut out);
+    out
+}
+
+// 二进制响应的content type，schema为file/binary或produces中没有json和xml时认为是二进制
+fn binary_response_type(
+    produces: &[String],
+    responses: &HashMap<String, Response>,
+) -> Option<String> {
+    let non_json_type = produces
+        .iter()
+        .find(|item| !item.contains("json") && item.as_str() != "*/*")
+        .cloned();
+    let has_json_type = produces
+        .iter()
+        .any(|item| item.contains("json") || item.contains("xml") || item.as_str() == "*/*");
+
+    let binary_schema = documented_response(responses)
+        .and_then(|response| response.schema.as_ref())
+        .map(|schema| match schema {
+            SchemaRef::Primitives(property) => {
+                property.type_.as_deref() == Some("file")
+                    || property.format.as_deref() == Some("binary")
+            }
+            _ => false,
+        })
+        .unwrap_or(false);
+
+    if binary_schema {
+        return Some(non_json_type.unwrap_or("application/octet-stream".to_string()));
+    }
+    if !has_json_type {
+        return non_json_type;
+    }
+    None
+}
+
+// 状态码排序，数字状态码在前，范围状态码（如2XX）排在同类的数字状态码之后，default在最后
+fn status_code_order(code: &str) -> (u8, u32, String) {
+    if code == "default" {
+        return (2, 0, code.to_string());
+    }
+    if let Some(class) = status_code_range(code) {
+        return (0, class * 100 + 99, code.to_uppercase());
+    }
+    match code.parse::<u32>() {
+        Ok(num) => (0, num, code.to_string()),
+        Err(_) => (1, 0, code.to_string()),
+    }
+}
+
+// 获得返回属性（嵌套获取）
+fn response_by_definitions<'a>(
+    original_ref: &'a String,
+    definitions: &'a HashMap<String, Definition>,
+    used_name: &mut HashSet<&'a String>,
+    depth: usize,
+    options: &SwaggerOptions,
+) -> Vec<DocxReturnParamInfo> {
+    // 检查是否循环引用
+    if used_name.contains(original_ref) {
+        return vec![];
+    }
+    used_name.insert(original_ref);
+
+    // 超过最大深度，只保留当前字段
+    if depth > options.max_depth {
+        return vec![DocxReturnParamInfo {
+            name: "".to_string(),
+            data_type: "object".to_string(),
+            desc: NESTED_TOO_DEEP.to_string(),
+        }];
+    }
+
+    let mut ps: Vec<DocxReturnParamInfo> = vec![];
+    if let Some(definition) = definitions.get(original_ref) {
+        if let Definition::Object(scheme) = definition {
+            for (name, prop) in schema_properties(scheme, definitions) {
+                let type_ = &prop.type_;
+                if let Some(map_type) = map_type_name(&prop.additional_properties) {
+                    // map
+                    ps.push(DocxReturnParamInfo {
+                        name: name.clone(),
+                        data_type: map_type,
+                        desc: prop.description.clone().unwrap_or("".to_string()),
+                    });
+                } else if let Some(type_value) = type_ {
+                    let data_type = type_value.clone();
+                    if "array" == data_type {
+                        // 列表
+                        if let Some(schema) = &prop.items {
+                            if let SchemaRef::Ref { ref_, original_ref } = schema {
+                                if let Some(original_ref_value) = original_ref {
+                                    let mut pst = response_by_definitions(
+                                        original_ref_value,
+                                        &definitions,
+                                        &mut used_name.clone(),
+                                        depth + 1,
+                                        options,
+                                    );
+                                    // 在每个参数前面加上"name[]."
+                                    pst.iter_mut().for_each(|item| {
+                                        item.name = field_path(
+                                            name,
+                                            true,
+                                            &item.name,
+                                            options.flatten_style,
+                                        )
+                                    });
+                                    ps.extend(pst);
+                                }
+                            } else if let SchemaRef::Primitives(property_box) = schema {
+                                // 属性
+                                let spi = DocxReturnParamInfo {
+                                    name: field_path(name, true, "", options.flatten_style),
+                                    data_type: property_box.type_.clone().unwrap_or("".to_string()),
+                                    desc: prop.description.clone().unwrap_or("".to_string()),
+                                };
+                                ps.push(spi);
+                            }
+                        }
+                    } else {
+                        // 属性
+                        let spi = DocxReturnParamInfo {
+                            name: name.clone(),
+                            data_type: leaf_type_name(prop, &data_type),
+                            desc: prop.description.clone().unwrap_or("".to_string()),
+                        };
+                        ps.push(spi);
+                    }
+                } else if let Some(original_ref_value) = &prop.original_ref {
+                    // 对象
+                    let mut pst = response_by_definitions(
+                        original_ref_value,
+                        &definitions,
+                        &mut used_name.clone(),
+                        depth + 1,
+                        options,
+                    );
+                    // 对象本身，超过最大深度时已有对应的行
+                    if !pst.iter().any(|item| item.name.is_empty()) {
+                        ps.push(DocxReturnParamInfo {
+                            name: name.clone(),
+                            data_type: "object".to_string(),
+                            desc: property_desc(prop, definitions),
+                        });
+                    }
+                    // 在每个参数前面加上"name."
+                    pst.iter_mut().for_each(|item| {
+                        item.name = field_path(name, false, &item.name, options.flatten_style)
+                    });
+                    ps.extend(pst);
+                }
+            }
+        }
+    }
+
+    // 类型字段的说明中列出子类型
+    if let Some(discriminator) = discriminator(original_ref, definitions) {
+        for item in ps
+            .iter_mut()
+            .filter(|item| &item.name == discriminator.field)
+        {
+            item.desc = discriminator.desc(&item.desc, original_ref);
+        }
+    }
+
+    return ps;
+}
+
+// 属性填充Value
+fn fill_value_by_definitions<'a>(
+    original_ref: &'a String,
+    value: &mut Value,
+    definitions: &'a HashMap<String, Definition>,
+    used_name: &mut HashSet<&'a String>,
+    depth: usize,
+    options: &SwaggerOptions,
+) {
+    // 检查是否循环引用
+    if used_name.contains(original_ref) {
+        return;
+    }
+    used_name.insert(original_ref);
+
+    // 超过最大深度，不再展开
+    if depth > options.max_depth {
+        *value = Value::String(NESTED_TOO_DEEP.to_string());
+        return;
+    }
+
+    // 多态对象使用第一个子类型作为示例，类型字段填写子类型名
+    let discriminator = discriminator(original_ref, definitions);
+    if let Some(subtype) = discriminator
+        .as_ref()
+        .and_then(|item| item.subtypes.first())
+    {
+        fill_value_by_definitions(subtype, value, definitions, used_name, depth, options);
+        return;
+    }
+
+    if let Some(definition) = definitions.get(original_ref) {
+        if let Definition::Object(scheme) = definition {
+            for (name, prop) in schema_properties(scheme, definitions) {
+                let type_ = &prop.type_;
+                if map_type_name(&prop.additional_properties).is_some() {
+                    // map
+                    let value_item = map_example_value(
+                        &prop.additional_properties,
+                        definitions,
+                        &mut used_name.clone(),
+                        depth + 1,
+                        options,
+                    );
+                    value
+                        .as_object_mut()
+                        .unwrap()
+                        .insert(name.to_string(), value_item);
+                } else if let Some(type_value) = type_ {
+                    let data_type = type_value.clone();
+                    if "array" == data_type {
+                        // 列表
+                        if let Some(schema) = &prop.items {
+                            if let SchemaRef::Ref { ref_, original_ref } = schema {
+                                if let Some(original_ref_value) = original_ref {
+                                    let mut value_item = Value::Object(Map::new());
+                                    fill_value_by_definitions(
+                                        original_ref_value,
+                                        &mut value_item,
+                                        &definitions,
+                                        &mut used_name.clone(),
+                                        depth + 1,
+                                        options,
+                                    );
+                                    value
+                                        .as_object_mut()
+                                        .unwrap()
+                                        .insert(name.to_string(), Value::Array(vec![value_item]));
+                                }
+                            } else if let SchemaRef::Primitives(property_box) = schema {
+                                // 属性
+                                // todo 空数组
+                                value
+                                    .as_object_mut()
+                                    .unwrap()
+                                    .insert(name.to_string(), Value::Array(vec![]));
+                            }
+                        }
+                    } else {
+                        // 属性
+                        value.as_object_mut().unwrap().insert(
+                            name.to_string(),
+                            gen_example_value(
+                                &name,
+                                &leaf_type_name(prop, &data_type),
+                                &prop.example,
+                                &prop.enum_,
+                                options,
+                            ),
+                        );
+                    }
+                } else if let Some(original_ref_value) = &prop.original_ref {
+                    // 对象
+                    let mut value_item = Value::Object(Map::new());
+                    fill_value_by_definitions(
+                        original_ref_value,
+                        &mut value_item,
+                        &definitions,
+                        &mut used_name.clone(),
+                        depth + 1,
+                        options,
+                    );
+                    value
+                        .as_object_mut()
+                        .unwrap()
+                        .insert(name.to_string(), value_item);
+                }
+            }
+        }
+    }
+    if let Some(discriminator) = discriminator
+        && let Some(object) = value.as_object_mut()
+    {
+        object.insert(
+            discriminator.field.clone(),
+            Value::String(original_ref.clone()),
+        );
+    }
+}
+
+fn param_by_definitions<'a>(
+    original_ref: &'a String,
+    definitions: &'a HashMap<String, Definition>,
+    used_name: &mut HashSet<&'a String>,
+    depth: usize,
+    options: &SwaggerOptions,
+) -> Vec<DocxParamInfo> {
+    // 检查是否循环引用
+    if used_name.contains(original_ref) {
+        return vec![];
+    }
+    used_name.insert(original_ref);
+
+    // 超过最大深度，只保留当前字段
+    if depth > options.max_depth {
+        return vec![DocxParamInfo::new("", "object").desc(NESTED_TOO_DEEP)];
+    }
+
+    let mut ps: Vec<DocxParamInfo> = vec![];
+    if let Some(definition) = definitions.get(original_ref) {
+        if let Definition::Object(scheme) = definition {
+            let require = schema_required(scheme, definitions);
+            for (name, prop) in schema_properties(scheme, definitions) {
+                let type_ = &prop.type_;
+                if let Some(map_type) = map_type_name(&prop.additional_properties) {
+                    // map
+                    let spi = DocxParamInfo {
+                        name: name.clone(),
+                        data_type: map_type,
+                        param_type: "".to_string(),
+                        required: if require.contains(name) {
+                            "Y".to_string()
+                        } else {
+                            "N".to_string()
+                        },
+                        desc: prop.description.clone().unwrap_or("".to_string()),
+                        example: map_example_value(
+                            &prop.additional_properties,
+                            definitions,
+                            &mut used_name.clone(),
+                            depth + 1,
+                            options,
+                        )
+                        .to_string(),
+                        constraints: "".to_string(),
+                    };
+                    ps.push(spi);
+                } else if let Some(type_value) = type_ {
+                    let data_type = leaf_type_name(prop, type_value);
+                    let spi = DocxParamInfo {
+                        name: name.clone(),
+                        data_type: param_type_name(&data_type, &prop.items, options),
+                        param_type: "".to_string(),
+                        required: if require.contains(name) {
+                            "Y".to_string()
+                        } else {
+                            "N".to_string()
+                        },
+                        desc: prop.description.clone().unwrap_or("".to_string()),
+                        example: example_text(gen_example_value(
+                            name,
+                            &data_type,
+                            &prop.example,
+                            &prop.enum_,
+                            options,
+                        )),
+                        constraints: constraints_text(false, &prop.multiple_of, prop.unique_items),
+                    };
+                    ps.push(spi);
+
+                    // 对象列表，展开为"name[].field"
+                    if "array" == type_value
+                        && let Some(SchemaRef::Ref {
+                            original_ref: Some(original_ref_value),
+                            ..
+                        }) = &prop.items
+                    {
+                        let mut pst = param_by_definitions(
+                            original_ref_value,
+                            definitions,
+                            &mut used_name.clone(),
+                            depth + 1,
+                            options,
+                        );
+                        pst.iter_mut().for_each(|item| {
+                            item.name = field_path(name, true, &item.name, options.flatten_style)
+                        });
+                        ps.extend(pst);
+                    }
+                } else if let Some(original_ref_value) = &prop.original_ref {
+                    // 对象，与返回参数一样展开为"name.field"
+                    let mut pst = param_by_definitions(
+                        original_ref_value,
+                        definitions,
+                        &mut used_name.clone(),
+                        depth + 1,
+                        options,
+                    );
+                    // 对象本身，超过最大深度时已有对应的行
+                    if !pst.iter().any(|item| item.name.is_empty()) {
+                        ps.push(DocxParamInfo {
+                            name: name.clone(),
+                            data_type: "object".to_string(),
+                            param_type: "".to_string(),
+                            required: if require.contains(name) {
+                                "Y".to_string()
+                            } else {
+                                "N".to_string()
+                            },
+                            desc: property_desc(prop, definitions),
+                            example: "".to_string(),
+                            constraints: "".to_string(),
+                        });
+                    }
+                    pst.iter_mut().for_each(|item| {
+                        item.name = field_path(name, false, &item.name, options.flatten_style)
+                    });
+                    ps.extend(pst);
+                }
+            }
+        }
+    }
+
+    // 类型字段的说明中列出子类型
+    if let Some(discriminator) = discriminator(original_ref, definitions) {
+        for item in ps
+            .iter_mut()
+            .filter(|item| &item.name == discriminator.field)
+        {
+            item.desc = discriminator.desc(&item.desc, original_ref);
+        }
+    }
+
+    return ps;
+}
+
+// 把请求体或响应体的schema生成为缩进的树形文本，root为根节点名
+fn schema_tree<'a>(
+    root: &str,
+    schema: &'a SchemaRef,
+    definitions: &'a HashMap<String, Definition>,
+    options: &SwaggerOptions,
+) -> Vec<String> {
+    match schema {
+        SchemaRef::Ref {
+            original_ref: Some(original_ref),
+            ..
+        } => {
+            let mut lines = vec![root.to_string()];
+            lines.extend(schema_tree_by_definitions(
+                original_ref,
+                definitions,
+                &mut HashSet::new(),
+                1,
+                options,
+            ));
+            lines
+        }
+        SchemaRef::Primitives(property_box) => match &property_box.items {
+            // 类似 List<Object>
+            Some(SchemaRef::Ref {
+                original_ref: Some(original_ref),
+                ..
+            }) => {
+                let mut lines = vec![format!("{root}[]")];
+                lines.extend(schema_tree_by_definitions(
+                    original_ref,
+                    definitions,
+                    &mut HashSet::new(),
+                    1,
+                    options,
+                ));
+                lines
+            }
+            _ => vec![format!(
+                "{root}: {}",
+                map_type_name(&property_box.additional_properties)
+                    .or(property_box.type_.clone())
+                    .unwrap_or("".to_string())
+            )],
+        },
+        _ => vec![],
+    }
+}
+
+// 对象的字段树，每深一层缩进两个空格，字段按名称排序
+fn schema_tree_by_definitions<'a>(
+    original_ref: &'a String,
+    definitions: &'a HashMap<String, Definition>,
+    used_name: &mut HashSet<&'a String>,
+    depth: usize,
+    options: &SwaggerOptions,
+) -> Vec<String> {
+    // 检查是否循环引用
+    if used_name.contains(original_ref) {
+        return vec![];
+    }
+    used_name.insert(original_ref);
+
+    let indent = "  ".repeat(depth);
+    // 超过最大深度，不再展开
+    if depth > options.max_depth {
+        return vec![format!("{indent}{NESTED_TOO_DEEP}")];
+    }
+
+    let mut lines: Vec<String> = vec![];
+    if let Some(Definition::Object(scheme)) = definitions.get(original_ref) {
+        let mut properties = schema_properties(scheme, definitions);
+        properties.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, prop) in properties {
+            if let Some(map_type) = map_type_name(&prop.additional_properties) {
+                lines.push(format!("{indent}{name}: {map_type}"));
+            } else if let Some(type_value) = &prop.type_ {
+                if "array" == type_value {
+                    match &prop.items {
+                        Some(SchemaRef::Ref {
+                            original_ref: Some(original_ref_value),
+                            ..
+                        }) => {
+                            // 对象列表
+                            lines.push(format!("{indent}{name}[]"));
+                            lines.extend(schema_tree_by_definitions(
+                                original_ref_value,
+                                definitions,
+                                &mut used_name.clone(),
+                                depth + 1,
+                                options,
+                            ));
+                        }
+                        Some(SchemaRef::Primitives(property_box)) => lines.push(format!(
+                            "{indent}{name}[]: {}",
+                            property_box.type_.clone().unwrap_or("".to_string())
+                        )),
+                        _ => lines.push(format!("{indent}{name}[]")),
+                    }
+                } else {
+                    lines.push(format!(
+                        "{indent}{name}: {}",
+                        leaf_type_name(prop, type_value)
+                    ));
+                }
+            } else if let Some(original_ref_value) = &prop.original_ref {
+                // 对象
+                lines.push(format!("{indent}{name}"));
+                lines.extend(schema_tree_by_definitions(
+                    original_ref_value,
+                    definitions,
+                    &mut used_name.clone(),
+                    depth + 1,
+                    options,
+                ));
+            }
+        }
+    }
+    lines
+}
+
+// 对象及allOf中的父对象、内联对象，父对象在前，自身在最后
+fn all_of_schemas<'a>(
+    schema: &'a Schema,
+    definitions: &'a HashMap<String, Definition>,
+) -> Vec<&'a Schema> {
+    fn collect<'a>(
+        schema: &'a Schema,
+        definitions: &'a HashMap<String, Definition>,
+        used_name: &mut HashSet<String>,
+        schemas: &mut Vec<&'a Schema>,
+    ) {
+        for item in schema.all_of.iter().flatten() {
+            match item {
+                AllOfSchema::Ref { ref_, original_ref } => {
+                    // 检查是否循环引用
+                    let name =
+                        ref_model_name(&Some(ref_.clone()), original_ref).unwrap_or_default();
+                    if used_name.insert(name.clone())
+                        && let Some(Definition::Object(parent)) = definitions.get(&name)
+                    {
+                        collect(parent, definitions, used_name, schemas);
+                    }
+                }
+                AllOfSchema::Object(part) => collect(part, definitions, used_name, schemas),
+            }
+        }
+        schemas.push(schema);
+    }
+
+    let mut schemas = vec![];
+    collect(schema, definitions, &mut HashSet::new(), &mut schemas);
+    schemas
+}
+
+// 对象的所有字段，包括allOf合并的字段，同名字段使用后面的定义
+fn schema_properties<'a>(
+    schema: &'a Schema,
+    definitions: &'a HashMap<String, Definition>,
+) -> Vec<(&'a String, &'a Property)> {
+    let mut properties: Vec<(&String, &Property)> = vec![];
+    for part in all_of_schemas(schema, definitions) {
+        for (name, prop) in part.properties.iter().flatten() {
+            match properties.iter_mut().find(|item| item.0 == name) {
+                Some(item) => item.1 = prop,
+                None => properties.push((name, prop)),
+            }
+        }
+    }
+    properties
+}
+
+// 对象的必填字段，包括allOf合并的必填字段
+fn schema_required(schema: &Schema, definitions: &HashMap<String, Definition>) -> Vec<String> {
+    all_of_schemas(schema, definitions)
+        .into_iter()
+        .flat_map(|part| part.required.clone().unwrap_or_default())
+        .collect()
+}
+
+// 多态对象的类型字段
+struct Discriminator<'a> {
+    // 类型字段名，来自自身或allOf引用的父对象
+    field: &'a String,
+    // allOf直接引用该对象的定义，按名称排序
+    subtypes: Vec<&'a String>,
+}
+
+impl Discriminator<'_> {
+    // 类型字段的说明，有子类型时列出子类型，否则为对象自身的类型名
+    fn desc(&self, desc: &str, name: &str) -> String {
+        let note = if self.subtypes.is_empty() {
+            format!("类型字段，值为{name}")
+        } else {
+            format!(
+                "类型字段，可选值：{}",
+                self.subtypes
+                    .iter()
+                    .map(|item| item.as_str())
+                    .collect::<Vec<&str>>()
+                    .join("、")
+            )
+        };
+        if desc.is_empty() {
+            note
+        } else {
+            format!("{desc}（{note}）")
+        }
+    }
+}
+
+fn discriminator<'a>(
+    original_ref: &str,
+    definitions: &'a HashMap<String, Definition>,
+) -> Option<Discriminator<'a>> {
+    let Some(Definition::Object(schema)) = definitions.get(original_ref) else {
+        return None;
+    };
+    let field = all_of_schemas(schema, definitions)
+        .into_iter()
+        .rev()
+        .find_map(|part| part.discriminator.as_ref())?;
+    let mut subtypes = definitions
+        .iter()
+        .filter(|(_, definition)| match definition {
+            Definition::Object(subtype) => subtype.all_of.iter().flatten().any(|item| {
+                matches!(item, AllOfSchema::Ref { ref_, original_ref: subtype_ref }
+                    if ref_model_name(&Some(ref_.clone()), subtype_ref).as_deref() == Some(original_ref))
+            }),
+            Definition::Other(_) => false,
+        })
+        .map(|(name, _)| name)
+        .collect::<Vec<&String>>();
+    subtypes.sort();
+    Some(Discriminator { field, subtypes })
+}
+
+// 属性的说明，属性本身没有说明时使用引用对象的title或description
+fn property_desc(prop: &Property, definitions: &HashMap<String, Definition>) -> String {
+    if let Some(desc) = &prop.description {
+        return desc.clone();
+    }
+    if let Some(original_ref) = &prop.original_ref
+        && let Some(Definition::Object(schema)) = definitions.get(original_ref)
+        && let Some(desc) = schema.title.as_ref().or(schema.description.as_ref())
+    {
+        return desc.clone();
+    }
+    "".to_string()
+}
+
+// 校验约束的说明，未设置的约束不输出
+fn constraints_text(
+    allow_empty_value: bool,
+    multiple_of: &Option<Number>,
+    unique_items: bool,
+) -> String {
+    let mut constraints: Vec<String> = vec![];
+    if allow_empty_value {
+        constraints.push("允许空值".to_string());
+    }
+    if let Some(multiple_of) = multiple_of {
+        constraints.push(format!("必须是{multiple_of}的倍数"));
+    }
+    if unique_items {
+        constraints.push("元素不能重复".to_string());
+    }
+    constraints.join("，")
+}
+
+// 生成测试数据
+// 参数的示例值，没有example时按类型生成
+fn param_example(param: &Parameter, options: &SwaggerOptions) -> String {
+    example_text(gen_example_value(
+        &param.name,
+        &param.param_type.clone().unwrap_or("string".to_string()),
+        &param.example,
+        &None,
+        options,
+    ))
+}
+
+// 示例值转为文本，字符串不带引号
+fn example_text(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+fn gen_example_value(
+    name: &String,
+    value_type: &String,
+    example: &Option<Value>,
+    enum_: &Option<Vec<Value>>,
+    options: &SwaggerOptions,
+) -> Value {
+    // 有示例数据则使用示例数据
+    if let Some(example_value) = example {
+        // return Value::String(example_value.clone());
+        return example_value.clone();
+    }
+
+    // 枚举使用第一个值
+    if let Some(first) = enum_.as_ref().and_then(|item| item.first()) {
+        return first.clone();
+    }
+
+    if "integer" == value_type {
+        if options.realistic_examples {
+            return Value::Number(Number::from(1 + name_seed(name) % 100));
+        }
+        return Value::Number(Number::from(1u32));
+    }
+    if "boolean" == value_type {
+        if options.realistic_examples {
+            return Value::Bool(name_seed(name).is_multiple_of(2));
+        }
+        return Value::Bool(false);
+    }
+    if FREE_FORM_OBJECT == value_type {
+        return Value::Object(Map::new());
+    }
+    if "string" == value_type {
+        // 日期
+        if name.to_lowercase().contains("time") || name.to_lowercase().contains("date") {
+            return Value::String("2025-10-13 20:26:09".to_string());
+        }
+        return Value::String("string".to_string());
+    }
+
+    return Value::Null;
+}
+
+// 由字段名哈希得到的固定种子，相同字段名每次生成相同的示例值
+fn name_seed(name: &str) -> u32 {
+    let digest = Sha256::digest(name.as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]])
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SwaggerDocument {
+    pub swagger: String,
+    pub info: Info,
+    pub host: String,
+    pub basePath: Option<String>,
+    pub schemes: Option<Vec<String>>,
+    pub tags: Vec<Tag>,
+    pub paths: HashMap<String, HashMap<String, Operation>>,
+    pub securityDefinitions: HashMap<String, SecurityDefinition>,
+    pub definitions: HashMap<String, Definition>,
+    // 接口间共享的参数，接口中的引用在解析前已替换为定义
+    #[serde(default)]
+    pub parameters: HashMap<String, Parameter>,
+    // 全局的content type，接口中没有设置时使用
+    #[serde(default)]
+    pub produces: Vec<String>,
+    #[serde(default)]
+    pub consumes: Vec<String>,
+    // 文档级的参考文档，渲染在标题下
+    #[serde(rename = "externalDocs")]
+    pub external_docs: Option<ExternalDocs>,
+}
+
+// 外部参考文档
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalDocs {
+    pub description: Option<String>,
+    pub url: String,
+}
+
+impl ExternalDocs {
+    // 有说明时为"说明 url"，否则只有url
+    fn text(&self) -> String {
+        match self.description.as_deref().map(str::trim) {
+            Some(description) if !description.is_empty() => {
+                format!("{description} {}", self.url)
+            }
+            _ => self.url.clone(),
+        }
+    }
+}
+
+// 没有参考文档时为空字符串，模板中不显示
+fn external_docs_text(external_docs: &Option<ExternalDocs>) -> String {
+    external_docs
+        .as_ref()
+        .map(ExternalDocs::text)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Info {
     pub version: String,
     pub title: String,
@@ -415,14 +2510,21 @@ pub struct Tag {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Operation {
+    // 没有分组的接口不会出现在文档中
+    #[serde(default)]
     pub tags: Vec<String>,
     pub summary: Option<String>,
     pub operation_id: String,
+    #[serde(default)]
     pub produces: Vec<String>,
     pub parameters: Option<Vec<Parameter>>,
     pub responses: HashMap<String, Response>,
     pub security: Option<Vec<HashMap<String, Vec<String>>>>,
     pub consumes: Option<Vec<String>>,
+    // 接口新增或变更的版本
+    #[serde(rename = "x-since")]
+    pub since: Option<String>,
+    pub external_docs: Option<ExternalDocs>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -436,6 +2538,15 @@ pub struct Parameter {
     pub param_type: Option<String>,
     pub format: Option<String>,
     pub schema: Option<SchemaRef>,
+    // type为array时的元素定义
+    pub items: Option<SchemaRef>,
+    #[serde(alias = "x-example")]
+    pub example: Option<Value>,
+    #[serde(default)]
+    pub allow_empty_value: bool,
+    pub multiple_of: Option<Number>,
+    #[serde(default)]
+    pub unique_items: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -443,6 +2554,7 @@ pub struct Parameter {
 pub struct Response {
     pub description: String,
     pub schema: Option<SchemaRef>,
+    pub examples: Option<HashMap<String, Value>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -468,6 +2580,24 @@ pub struct Schema {
     pub required: Option<Vec<String>>,
     pub properties: Option<HashMap<String, Property>>,
     pub title: Option<String>,
+    pub description: Option<String>,
+    pub additional_properties: Option<AdditionalProperties>,
+    // 多态对象的类型字段名，子类型通过allOf引用该对象
+    pub discriminator: Option<String>,
+    pub all_of: Option<Vec<AllOfSchema>>,
+}
+
+// allOf中的一项，引用的父对象或内联对象
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AllOfSchema {
+    Ref {
+        #[serde(rename = "$ref")]
+        ref_: String,
+        #[serde(rename = "originalRef")]
+        original_ref: Option<String>,
+    },
+    Object(Schema),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -478,11 +2608,28 @@ pub struct Property {
     pub description: Option<String>,
     pub format: Option<String>,
     pub example: Option<Value>,
+    #[serde(rename = "enum")]
+    pub enum_: Option<Vec<Value>>,
     pub items: Option<SchemaRef>,
     #[serde(rename = "$ref")]
     pub ref_: Option<String>,
     #[serde(rename = "originalRef")]
     pub original_ref: Option<String>,
+    pub multiple_of: Option<Number>,
+    #[serde(default)]
+    pub unique_items: bool,
+    // 内联对象的字段，只用于判断object是否定义了结构，不展开
+    pub properties: Option<HashMap<String, Property>>,
+    // map类型（key为string）的值定义
+    pub additional_properties: Option<AdditionalProperties>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AdditionalProperties {
+    // true表示值可以是任意类型
+    Any(bool),
+    Schema(Box<SchemaRef>),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -490,8 +2637,16 @@ pub struct Property {
 pub struct SecurityDefinition {
     #[serde(rename = "type")]
     pub type_: String,
+    // apiKey的参数名和位置，oauth2、basic没有
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub in_: String,
+    // oauth2的授权方式、地址和可用的scope（scope -> 说明）
+    pub flow: Option<String>,
+    pub authorization_url: Option<String>,
+    pub token_url: Option<String>,
+    pub scopes: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -502,17 +2657,59 @@ pub enum Definition {
 }
 
 // 下面是docx的模板
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DocxProjectInfo {
     // 项目名称
     pub name: String,
 
     // 接口描述
     pub apis: HashMap<String, Vec<DocxApiInfo>>,
+
+    // 分组描述，渲染在分组标题下
+    pub tag_descs: HashMap<String, String>,
+
+    // 分组编号，渲染在分组标题前
+    pub tag_numbers: HashMap<String, String>,
+
+    // 生成时间，RFC3339格式，为空时不显示
+    pub generated_at: String,
+
+    // swagger的来源，为空时不显示
+    pub source: String,
+
+    // 接口文档的版本，取自info.version
+    pub version: String,
+
+    // 文档级的参考文档，为空时不显示
+    pub external_docs: String,
+
+    // 是否生成封面，封面包括logo、项目名称、版本和生成时间，之后分页
+    pub cover: bool,
+
+    // 封面上的logo，base64编码的图片，为空时不显示
+    pub logo: String,
+
+    // 数据模型，渲染为附录，为空时不生成附录
+    pub models: Vec<DocxModelInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DocxModelInfo {
+    // 模型名称
+    pub name: String,
+
+    // 模型说明
+    pub desc: String,
+
+    // 字段列表，param_type为空
+    pub fields: Vec<DocxParamInfo>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DocxApiInfo {
+    // 编号，如"1.3"，为空时不显示
+    pub number: String,
+
     // 接口名称
     pub name: String,
 
@@ -542,9 +2739,27 @@ pub struct DocxApiInfo {
 
     // 返回参数示例
     pub return_params_example: String,
+
+    // 返回参数示例的格式，如application/json，二进制响应为空
+    pub example_media_type: String,
+
+    // 是否为二进制响应（文件下载）
+    pub is_binary_response: bool,
+
+    // curl调用示例
+    pub example_curl: String,
+
+    // 请求体和响应体的树形结构，每层缩进两个空格
+    pub schema_tree: String,
+
+    // 认证说明，oauth2包含接口要求的scope
+    pub auth: String,
+
+    // 接口的参考文档，为空时不显示
+    pub external_docs: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DocxParamInfo {
     // 参数名
     pub name: String,
@@ -560,9 +2775,15 @@ pub struct DocxParamInfo {
 
     // 说明
     pub desc: String,
+
+    // 示例值
+    pub example: String,
+
+    // 校验约束，如"必须是5的倍数"
+    pub constraints: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DocxStatusCode {
     // 状态码
     pub code: String,
@@ -574,7 +2795,7 @@ pub struct DocxStatusCode {
     pub explain: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DocxReturnParamInfo {
     // 返回属性名
     pub name: String,
@@ -585,3 +2806,1644 @@ pub struct DocxReturnParamInfo {
     // 说明
     pub desc: String,
 }
+
+impl DocxProjectInfo {
+    pub fn new(name: impl Into<String>) -> DocxProjectInfo {
+        DocxProjectInfo {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    // 添加接口到对应的分组
+    pub fn add_api(mut self, tag: impl Into<String>, api: DocxApiInfo) -> Self {
+        self.apis.entry(tag.into()).or_default().push(api);
+        self
+    }
+
+    // 设置分组描述
+    pub fn tag_desc(mut self, tag: impl Into<String>, desc: impl Into<String>) -> Self {
+        self.tag_descs.insert(tag.into(), desc.into());
+        self
+    }
+
+    pub fn generated_at(mut self, generated_at: impl Into<String>) -> Self {
+        self.generated_at = generated_at.into();
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn external_docs(mut self, external_docs: impl Into<String>) -> Self {
+        self.external_docs = external_docs.into();
+        self
+    }
+
+    // 生成封面，logo为base64编码的图片，为空时不显示
+    pub fn cover(mut self, logo: impl Into<String>) -> Self {
+        self.cover = true;
+        self.logo = logo.into();
+        self
+    }
+
+    // 设置分组编号
+    pub fn tag_number(mut self, tag: impl Into<String>, number: impl Into<String>) -> Self {
+        self.tag_numbers.insert(tag.into(), number.into());
+        self
+    }
+
+    // 添加附录中的数据模型
+    pub fn add_model(mut self, model: DocxModelInfo) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    // 所有分组中的接口数量
+    pub fn endpoint_count(&self) -> usize {
+        self.apis.values().map(|apis| apis.len()).sum()
+    }
+}
+
+impl DocxModelInfo {
+    pub fn new(name: impl Into<String>) -> Self {
+        DocxModelInfo {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn desc(mut self, desc: impl Into<String>) -> Self {
+        self.desc = desc.into();
+        self
+    }
+
+    pub fn add_field(mut self, field: DocxParamInfo) -> Self {
+        self.fields.push(field);
+        self
+    }
+}
+
+impl DocxApiInfo {
+    pub fn new(name: impl Into<String>, method: impl Into<String>, url: impl Into<String>) -> Self {
+        DocxApiInfo {
+            name: name.into(),
+            method: method.into(),
+            url: url.into(),
+            return_type: "*/*".to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn number(mut self, number: impl Into<String>) -> Self {
+        self.number = number.into();
+        self
+    }
+
+    pub fn desc(mut self, desc: impl Into<String>) -> Self {
+        self.desc = desc.into();
+        self
+    }
+
+    pub fn api_type(mut self, api_type: impl Into<String>) -> Self {
+        self.api_type = api_type.into();
+        self
+    }
+
+    pub fn return_type(mut self, return_type: impl Into<String>) -> Self {
+        self.return_type = return_type.into();
+        self
+    }
+
+    pub fn add_query_param(mut self, param: DocxParamInfo) -> Self {
+        self.query_params.push(param);
+        self
+    }
+
+    pub fn add_status_code(mut self, status_code: DocxStatusCode) -> Self {
+        self.status_codes.push(status_code);
+        self
+    }
+
+    pub fn add_return_param(mut self, return_param: DocxReturnParamInfo) -> Self {
+        self.return_params.push(return_param);
+        self
+    }
+
+    pub fn return_params_example(mut self, example: impl Into<String>) -> Self {
+        self.return_params_example = example.into();
+        self
+    }
+
+    pub fn example_media_type(mut self, example_media_type: impl Into<String>) -> Self {
+        self.example_media_type = example_media_type.into();
+        self
+    }
+
+    pub fn binary_response(mut self, is_binary_response: bool) -> Self {
+        self.is_binary_response = is_binary_response;
+        self
+    }
+
+    pub fn schema_tree(mut self, schema_tree: impl Into<String>) -> Self {
+        self.schema_tree = schema_tree.into();
+        self
+    }
+
+    pub fn auth(mut self, auth: impl Into<String>) -> Self {
+        self.auth = auth.into();
+        self
+    }
+
+    pub fn external_docs(mut self, external_docs: impl Into<String>) -> Self {
+        self.external_docs = external_docs.into();
+        self
+    }
+}
+
+impl DocxParamInfo {
+    pub fn new(name: impl Into<String>, data_type: impl Into<String>) -> Self {
+        DocxParamInfo {
+            name: name.into(),
+            data_type: data_type.into(),
+            required: "N".to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn param_type(mut self, param_type: impl Into<String>) -> Self {
+        self.param_type = param_type.into();
+        self
+    }
+
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = if required { "Y" } else { "N" }.to_string();
+        self
+    }
+
+    pub fn desc(mut self, desc: impl Into<String>) -> Self {
+        self.desc = desc.into();
+        self
+    }
+
+    pub fn example(mut self, example: impl Into<String>) -> Self {
+        self.example = example.into();
+        self
+    }
+
+    pub fn constraints(mut self, constraints: impl Into<String>) -> Self {
+        self.constraints = constraints.into();
+        self
+    }
+}
+
+impl DocxStatusCode {
+    pub fn new(code: impl Into<String>, desc: impl Into<String>) -> Self {
+        DocxStatusCode {
+            code: code.into(),
+            desc: desc.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn explain(mut self, explain: impl Into<String>) -> Self {
+        self.explain = explain.into();
+        self
+    }
+}
+
+impl DocxReturnParamInfo {
+    pub fn new(name: impl Into<String>, data_type: impl Into<String>) -> Self {
+        DocxReturnParamInfo {
+            name: name.into(),
+            data_type: data_type.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn desc(mut self, desc: impl Into<String>) -> Self {
+        self.desc = desc.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_definitions() -> HashMap<String, Definition> {
+        serde_json::from_value(serde_json::json!({
+            "Page": {"type": "object", "properties": {
+                "items": {"type": "array", "items": {"$ref": "#/definitions/Order", "originalRef": "Order"}}
+            }},
+            "Order": {"type": "object", "properties": {
+                "no": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "lines": {"type": "array", "items": {"$ref": "#/definitions/Line", "originalRef": "Line"}}
+            }},
+            "Line": {"type": "object", "properties": {
+                "sku": {"type": "string"}
+            }}
+        }))
+        .unwrap()
+    }
+
+    fn return_param_names(options: &SwaggerOptions) -> Vec<String> {
+        let definitions = test_definitions();
+        let mut names = response_by_definitions(
+            &"Page".to_string(),
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            options,
+        )
+        .into_iter()
+        .map(|item| item.name)
+        .collect::<Vec<String>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn flatten_nested_array_json_style() {
+        let names = return_param_names(&SwaggerOptions::default());
+        assert_eq!(
+            names,
+            vec!["items[].lines[].sku", "items[].no", "items[].tags[]"]
+        );
+    }
+
+    #[test]
+    fn flatten_nested_array_dot_style() {
+        let options = SwaggerOptions {
+            flatten_style: FlattenStyle::Dot,
+            ..SwaggerOptions::default()
+        };
+        let names = return_param_names(&options);
+        assert_eq!(
+            names,
+            vec!["items.[].lines.[].sku", "items.[].no", "items.[].tags.[]"]
+        );
+    }
+
+    #[test]
+    fn stop_expanding_beyond_max_depth() {
+        let options = SwaggerOptions {
+            max_depth: 2,
+            ..SwaggerOptions::default()
+        };
+        let names = return_param_names(&options);
+        assert_eq!(
+            names,
+            vec!["items[].lines[]", "items[].no", "items[].tags[]"]
+        );
+
+        let definitions = test_definitions();
+        let mut value = Value::Object(Map::new());
+        fill_value_by_definitions(
+            &"Page".to_string(),
+            &mut value,
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &options,
+        );
+        assert_eq!(
+            value["items"][0]["lines"][0],
+            Value::String(NESTED_TOO_DEEP.to_string())
+        );
+    }
+
+    #[test]
+    fn expand_array_of_refs_in_params() {
+        let definitions = test_definitions();
+        let mut names = param_by_definitions(
+            &"Page".to_string(),
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &SwaggerOptions::default(),
+        )
+        .into_iter()
+        .map(|item| (item.name, item.data_type))
+        .collect::<Vec<(String, String)>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                ("items".to_string(), "array".to_string()),
+                ("items[].lines".to_string(), "array".to_string()),
+                ("items[].lines[].sku".to_string(), "string".to_string()),
+                ("items[].no".to_string(), "string".to_string()),
+                ("items[].tags".to_string(), "array".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn array_params_show_item_types() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "订单"}],
+            "paths": {"/orders": {"post": {
+                "tags": ["订单"], "operationId": "create", "produces": [],
+                "responses": {},
+                "parameters": [
+                    {"name": "ids", "in": "query", "required": false, "type": "array",
+                     "items": {"type": "integer"}},
+                    {"name": "status", "in": "query", "required": false, "type": "string"},
+                    {"name": "page", "in": "body", "required": true,
+                     "schema": {"$ref": "#/definitions/Page", "originalRef": "Page"}}
+                ]
+            }}},
+            "securityDefinitions": {},
+            "definitions": test_definitions()
+        })
+        .to_string();
+        let data_types = |options: &SwaggerOptions| {
+            let project = parse_swagger(swagger.as_bytes(), options).unwrap();
+            project.apis["订单"][0]
+                .query_params
+                .iter()
+                .map(|item| (item.name.clone(), item.data_type.clone()))
+                .collect::<HashMap<String, String>>()
+        };
+
+        let data_type = data_types(&SwaggerOptions::default());
+        assert_eq!(data_type["ids"], "array");
+        let data_type = data_types(&SwaggerOptions {
+            array_item_types: true,
+            ..SwaggerOptions::default()
+        });
+        assert_eq!(data_type["ids"], "array<integer>");
+        assert_eq!(data_type["status"], "string");
+        assert_eq!(data_type["body.items"], "array<Order>");
+        assert_eq!(data_type["body.items[].tags"], "array<string>");
+        assert_eq!(data_type["body.items[].no"], "string");
+    }
+
+    #[test]
+    fn schema_tree_indents_nested_fields() {
+        let definitions = test_definitions();
+        let schema: SchemaRef = serde_json::from_value(serde_json::json!({
+            "$ref": "#/definitions/Page", "originalRef": "Page"
+        }))
+        .unwrap();
+        assert_eq!(
+            schema_tree("body", &schema, &definitions, &SwaggerOptions::default()).join("\n"),
+            "body\n  items[]\n    lines[]\n      sku: string\n    no: string\n    tags[]: string"
+        );
+
+        let options = SwaggerOptions {
+            max_depth: 1,
+            ..SwaggerOptions::default()
+        };
+        assert_eq!(
+            schema_tree("body", &schema, &definitions, &options),
+            vec!["body", "  items[]", &format!("    {NESTED_TOO_DEEP}")]
+        );
+    }
+
+    #[test]
+    fn object_ref_desc_falls_back_to_definition_title() {
+        let definitions: HashMap<String, Definition> = serde_json::from_value(serde_json::json!({
+            "User": {"type": "object", "properties": {
+                "address": {"$ref": "#/definitions/Address", "originalRef": "Address"},
+                "company": {"$ref": "#/definitions/Company", "originalRef": "Company", "description": "所在公司"}
+            }},
+            "Address": {"type": "object", "title": "地址", "properties": {
+                "city": {"type": "string"}
+            }},
+            "Company": {"type": "object", "title": "公司", "properties": {
+                "name": {"type": "string"}
+            }}
+        }))
+        .unwrap();
+
+        let ps = response_by_definitions(
+            &"User".to_string(),
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &SwaggerOptions::default(),
+        );
+        let desc_of = |name: &str| {
+            ps.iter()
+                .find(|item| item.name == name)
+                .map(|item| item.desc.clone())
+        };
+        assert_eq!(desc_of("address"), Some("地址".to_string()));
+        assert_eq!(desc_of("company"), Some("所在公司".to_string()));
+        assert_eq!(desc_of("address.city"), Some("".to_string()));
+
+        let params = param_by_definitions(
+            &"User".to_string(),
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &SwaggerOptions::default(),
+        );
+        let address = params.iter().find(|item| item.name == "address").unwrap();
+        assert_eq!(address.desc, "地址");
+    }
+
+    #[test]
+    fn body_params_expand_nested_objects() {
+        let definitions: HashMap<String, Definition> = serde_json::from_value(serde_json::json!({
+            "Order": {"type": "object", "required": ["user"], "properties": {
+                "user": {"$ref": "#/definitions/User", "originalRef": "User", "description": "下单用户"}
+            }},
+            "User": {"type": "object", "required": ["name"], "properties": {
+                "name": {"type": "string", "description": "姓名"},
+                "address": {"$ref": "#/definitions/Address", "originalRef": "Address"}
+            }},
+            "Address": {"type": "object", "properties": {
+                "city": {"type": "string", "description": "城市"}
+            }}
+        }))
+        .unwrap();
+
+        let params = param_by_definitions(
+            &"Order".to_string(),
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &SwaggerOptions::default(),
+        );
+        let row = |name: &str| {
+            params.iter().find(|item| item.name == name).map(|item| {
+                (
+                    item.data_type.as_str(),
+                    item.required.as_str(),
+                    item.desc.as_str(),
+                )
+            })
+        };
+        assert_eq!(row("user"), Some(("object", "Y", "下单用户")));
+        assert_eq!(row("user.name"), Some(("string", "Y", "姓名")));
+        assert_eq!(row("user.address.city"), Some(("string", "N", "城市")));
+    }
+
+    #[test]
+    fn document_discriminator_subtypes() {
+        let definitions: HashMap<String, Definition> = serde_json::from_value(serde_json::json!({
+            "Payment": {"type": "object", "discriminator": "kind", "required": ["kind"], "properties": {
+                "kind": {"type": "string", "description": "支付方式"},
+                "amount": {"type": "integer"}
+            }},
+            "Card": {"allOf": [
+                {"$ref": "#/definitions/Payment"},
+                {"type": "object", "required": ["cardNo"], "properties": {
+                    "cardNo": {"type": "string", "description": "卡号"}
+                }}
+            ]},
+            "Wallet": {"allOf": [
+                {"$ref": "#/definitions/Payment", "originalRef": "Payment"},
+                {"type": "object", "properties": {"walletId": {"type": "string"}}}
+            ]}
+        }))
+        .unwrap();
+        let options = SwaggerOptions::default();
+
+        // 基类列出子类型
+        let params = param_by_definitions(
+            &"Payment".to_string(),
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &options,
+        );
+        let kind = params.iter().find(|item| item.name == "kind").unwrap();
+        assert_eq!(kind.desc, "支付方式（类型字段，可选值：Card、Wallet）");
+
+        // 子类型合并父对象的字段和必填
+        let mut params = param_by_definitions(
+            &"Card".to_string(),
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &options,
+        )
+        .into_iter()
+        .map(|item| (item.name, item.required, item.desc))
+        .collect::<Vec<_>>();
+        params.sort();
+        assert_eq!(
+            params,
+            vec![
+                ("amount".to_string(), "N".to_string(), "".to_string()),
+                ("cardNo".to_string(), "Y".to_string(), "卡号".to_string()),
+                (
+                    "kind".to_string(),
+                    "Y".to_string(),
+                    "支付方式（类型字段，值为Card）".to_string()
+                ),
+            ]
+        );
+
+        // 示例使用第一个子类型
+        let mut value = Value::Object(Map::new());
+        fill_value_by_definitions(
+            &"Payment".to_string(),
+            &mut value,
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &options,
+        );
+        assert_eq!(value["kind"], "Card");
+        assert!(value.get("cardNo").is_some());
+        assert!(value.get("walletId").is_none());
+    }
+
+    #[test]
+    fn enum_example_uses_first_member() {
+        let status = gen_example_value(
+            &"status".to_string(),
+            &"string".to_string(),
+            &None,
+            &Some(vec![Value::from("ENABLED"), Value::from("DISABLED")]),
+            &SwaggerOptions::default(),
+        );
+        assert_eq!(status, Value::from("ENABLED"));
+
+        let name = gen_example_value(
+            &"name".to_string(),
+            &"string".to_string(),
+            &None,
+            &None,
+            &SwaggerOptions::default(),
+        );
+        assert_eq!(name, Value::from("string"));
+    }
+
+    #[test]
+    fn realistic_examples_vary_by_name() {
+        let example = |name: &str, value_type: &str, realistic: bool| {
+            let options = SwaggerOptions {
+                realistic_examples: realistic,
+                ..SwaggerOptions::default()
+            };
+            gen_example_value(
+                &name.to_string(),
+                &value_type.to_string(),
+                &None,
+                &None,
+                &options,
+            )
+        };
+
+        // 默认固定为1和false
+        assert_eq!(example("age", "integer", false), Value::from(1));
+        assert_eq!(example("count", "integer", false), Value::from(1));
+        assert_eq!(example("enabled", "boolean", false), Value::from(false));
+
+        // 不同字段名的值不同，相同字段名每次相同
+        let age = example("age", "integer", true);
+        assert_ne!(age, example("count", "integer", true));
+        assert_eq!(age, example("age", "integer", true));
+        let age = age.as_u64().unwrap();
+        assert!((1..=100).contains(&age));
+        assert_ne!(
+            example("enabled", "boolean", true),
+            example("deleted", "boolean", true)
+        );
+    }
+    #[test]
+    fn detect_binary_download_response() {
+        let responses: HashMap<String, Response> = serde_json::from_value(serde_json::json!({
+            "200": {"description": "OK", "schema": {"type": "file"}}
+        }))
+        .unwrap();
+        assert_eq!(
+            binary_response_type(&["*/*".to_string()], &responses),
+            Some("application/octet-stream".to_string())
+        );
+
+        let responses: HashMap<String, Response> = serde_json::from_value(serde_json::json!({
+            "200": {"description": "OK", "schema": {"$ref": "#/definitions/R", "originalRef": "R"}}
+        }))
+        .unwrap();
+        assert_eq!(
+            binary_response_type(&["application/vnd.ms-excel".to_string()], &responses),
+            Some("application/vnd.ms-excel".to_string())
+        );
+        assert_eq!(
+            binary_response_type(&["application/json".to_string()], &responses),
+            None
+        );
+    }
+
+    #[test]
+    fn declared_response_example_by_produces() {
+        let response: Response = serde_json::from_value(serde_json::json!({
+            "description": "OK",
+            "examples": {
+                "text/plain": "ok",
+                "application/json": {"id": 1}
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            response_example(&response, &["text/plain".to_string()]),
+            Some(("text/plain".to_string(), Value::String("ok".to_string())))
+        );
+        assert_eq!(
+            response_example(&response, &["*/*".to_string()]),
+            Some(("application/json".to_string(), serde_json::json!({"id": 1})))
+        );
+
+        let response: Response =
+            serde_json::from_value(serde_json::json!({"description": "OK"})).unwrap();
+        assert_eq!(
+            response_example(&response, &["application/json".to_string()]),
+            None
+        );
+    }
+
+    #[test]
+    fn xml_example_for_xml_only_produces() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "订单"}],
+            "paths": {"/orders": {"get": {
+                "tags": ["订单"], "operationId": "list", "produces": ["application/xml"],
+                "responses": {"200": {"description": "OK",
+                    "schema": {"$ref": "#/definitions/Order", "originalRef": "Order"}}}
+            }}},
+            "securityDefinitions": {},
+            "definitions": {"Order": {"type": "object", "properties": {
+                "no": {"type": "string"},
+                "lines": {"type": "array",
+                    "items": {"$ref": "#/definitions/Line", "originalRef": "Line"}}
+            }}, "Line": {"type": "object", "properties": {"sku": {"type": "string"}}}}
+        })
+        .to_string();
+
+        // 默认仍为json示例，标注格式
+        let project = parse_swagger(swagger.as_bytes(), &SwaggerOptions::default()).unwrap();
+        let api = &project.apis["订单"][0];
+        assert!(!api.is_binary_response);
+        assert_eq!(api.example_media_type, "application/json");
+        assert!(api.return_params_example.starts_with('{'));
+
+        let options = SwaggerOptions {
+            xml_examples: true,
+            ..SwaggerOptions::default()
+        };
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        let api = &project.apis["订单"][0];
+        assert_eq!(api.example_media_type, "application/xml");
+        assert!(api.return_params_example.starts_with("<?xml"));
+        assert!(api.return_params_example.contains("<Order>"));
+        assert!(
+            api.return_params_example
+                .contains("<lines><sku>string</sku></lines>")
+        );
+        assert!(api.return_params_example.contains("<no>string</no>"));
+
+        // 渲染时转义，生成的docx可以正常读取
+        let docx = render_swagger_docx(&project, "zh").unwrap();
+        let text = crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap());
+        assert!(text.contains("返回值（application/xml）"));
+        assert!(text.contains("<Order><lines>"));
+    }
+
+    #[test]
+    fn strip_html_tags_conservatively() {
+        assert_eq!(strip_html("<p>用户名</p>"), "用户名");
+        assert_eq!(strip_html("第一行<br>第二行<br/>"), "第一行\n第二行");
+        assert_eq!(
+            strip_html("<b>注意</b>：<span style=\"color:red\">必填</span>"),
+            "注意：必填"
+        );
+        assert_eq!(strip_html("返回List<String>"), "返回List<String>");
+        assert_eq!(strip_html("a < b 且 c > d"), "a < b 且 c > d");
+    }
+
+    #[test]
+    fn build_curl_with_path_query_auth_and_body() {
+        let operation: Operation = serde_json::from_value(serde_json::json!({
+            "tags": ["用户"],
+            "operationId": "updateUser",
+            "produces": ["application/json"],
+            "consumes": ["application/json"],
+            "parameters": [
+                {"name": "id", "in": "path", "required": true, "type": "integer"},
+                {"name": "dryRun", "in": "query", "required": false, "type": "boolean"},
+                {"name": "status", "in": "query", "required": false, "type": "string",
+                 "x-example": "ACTIVE"},
+                {"name": "user", "in": "body", "required": true,
+                 "schema": {"$ref": "#/definitions/Line", "originalRef": "Line"}}
+            ],
+            "responses": {"200": {"description": "OK"}},
+            "security": [{"token": []}]
+        }))
+        .unwrap();
+        let security_definitions: HashMap<String, SecurityDefinition> =
+            serde_json::from_value(serde_json::json!({
+                "token": {"type": "apiKey", "name": "Authorization", "in": "header"}
+            }))
+            .unwrap();
+
+        let curl = build_example_curl(
+            "put",
+            "https://api.example.com/v1/users/{id}",
+            &operation,
+            &test_definitions(),
+            &security_definitions,
+            &SwaggerOptions::default(),
+        );
+        assert_eq!(
+            curl,
+            "curl -X PUT 'https://api.example.com/v1/users/1?dryRun=false&status=ACTIVE' \
+             -H 'Authorization: <Authorization>' \
+             -H 'Content-Type: application/json' -d '{\"sku\":\"string\"}'"
+        );
+    }
+
+    #[test]
+    fn oauth2_security_lists_required_scopes() {
+        let operation: Operation = serde_json::from_value(serde_json::json!({
+            "tags": ["用户"],
+            "operationId": "listUsers",
+            "produces": ["application/json"],
+            "responses": {"200": {"description": "OK"}},
+            "security": [{"oauth": ["read:user", "admin"]}, {"token": []}]
+        }))
+        .unwrap();
+        let security_definitions: HashMap<String, SecurityDefinition> =
+            serde_json::from_value(serde_json::json!({
+                "oauth": {
+                    "type": "oauth2",
+                    "flow": "accessCode",
+                    "authorizationUrl": "https://auth.example.com/authorize",
+                    "tokenUrl": "https://auth.example.com/token",
+                    "scopes": {"read:user": "读取用户", "write:user": "修改用户", "admin": ""}
+                },
+                "token": {"type": "apiKey", "name": "Authorization", "in": "header"}
+            }))
+            .unwrap();
+
+        assert_eq!(
+            security_desc(&operation, &security_definitions),
+            "oauth（OAuth2 accessCode，授权地址: https://auth.example.com/authorize，\
+             令牌地址: https://auth.example.com/token，scopes: read:user（读取用户）, admin）；\
+             token（apiKey，header参数 Authorization）"
+        );
+        let curl = build_example_curl(
+            "get",
+            "https://api.example.com/users",
+            &operation,
+            &HashMap::new(),
+            &security_definitions,
+            &SwaggerOptions::default(),
+        );
+        assert!(curl.contains("-H 'Authorization: Bearer <access_token>'"));
+    }
+
+    #[test]
+    fn filter_operations_by_since() {
+        assert_eq!(compare_version("v1.10.0", "1.9"), Ordering::Greater);
+        assert_eq!(compare_version("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_version("2.0.0-beta", "2.0.0"), Ordering::Less);
+
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "用户服务", "version": "1.10.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "用户"}],
+            "paths": {
+                "/old": {"get": {"tags": ["用户"], "summary": "旧接口", "operationId": "old",
+                    "produces": [], "responses": {}, "x-since": "1.2.0"}},
+                "/new": {"get": {"tags": ["用户"], "summary": "新接口", "operationId": "new",
+                    "produces": [], "responses": {}, "x-since": "v1.10"}},
+                "/none": {"get": {"tags": ["用户"], "summary": "未标注", "operationId": "none",
+                    "produces": [], "responses": {}}}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let names = |options: &SwaggerOptions| {
+            let project = parse_swagger(swagger.as_bytes(), options).unwrap();
+            let mut names = project.apis["用户"]
+                .iter()
+                .map(|api| api.name.clone())
+                .collect::<Vec<String>>();
+            names.sort();
+            names
+        };
+
+        let mut options = SwaggerOptions {
+            since: Some("1.3".to_string()),
+            ..SwaggerOptions::default()
+        };
+        assert_eq!(names(&options), vec!["新接口"]);
+        options.include_unversioned = true;
+        assert_eq!(names(&options), vec!["新接口", "未标注"]);
+        assert_eq!(names(&SwaggerOptions::default()).len(), 3);
+
+        // 过滤后没有接口，分组仍然保留
+        options.since = Some("2.0".to_string());
+        options.include_unversioned = false;
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        assert!(project.apis.contains_key("用户"));
+        assert_eq!(project.endpoint_count(), 0);
+    }
+
+    #[test]
+    fn group_by_first_path_segment() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "商城", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "用户", "description": "用户管理"}],
+            "paths": {
+                "/users": {"get": {"tags": ["用户"], "summary": "用户列表", "operationId": "users",
+                    "produces": [], "responses": {}}},
+                "/users/{id}": {"get": {"summary": "用户详情", "operationId": "user",
+                    "produces": [], "responses": {}}},
+                "/orders": {"post": {"summary": "下单", "operationId": "order",
+                    "produces": [], "responses": {}}},
+                "/": {"get": {"summary": "首页", "operationId": "index",
+                    "produces": [], "responses": {}}}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let mut options = SwaggerOptions {
+            group_by: GroupBy::Path,
+            ..SwaggerOptions::default()
+        };
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        let mut groups = project
+            .apis
+            .iter()
+            .map(|(group, apis)| {
+                let urls = apis.iter().map(|api| api.url.as_str()).collect::<Vec<_>>();
+                (group.as_str(), urls)
+            })
+            .collect::<Vec<_>>();
+        groups.sort();
+        assert_eq!(
+            groups,
+            vec![
+                ("/", vec!["/"]),
+                ("orders", vec!["/orders"]),
+                ("users", vec!["/users", "/users/{id}"]),
+            ]
+        );
+        assert!(project.tag_descs.is_empty());
+
+        // 没有tags的接口不再提示，过滤时使用路径分组名
+        options.exclude_tags = vec!["orders".to_string(), "用户".to_string()];
+        let (project, diagnostics) = parse_swagger_with_diagnostics(swagger.as_bytes(), &options);
+        assert!(!project.apis.contains_key("orders"));
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|item| item.message.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["要过滤的分组用户不存在"]
+        );
+    }
+
+    #[test]
+    fn filter_tags_include_takes_precedence() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "商城", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "用户"}, {"name": "订单"}, {"name": "内部"}],
+            "paths": {
+                "/users": {"get": {"tags": ["用户"], "summary": "用户列表", "operationId": "users",
+                    "produces": [], "responses": {}}},
+                "/orders": {"get": {"tags": ["订单", "内部"], "summary": "订单列表", "operationId": "orders",
+                    "produces": [], "responses": {}}}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let tags = |options: &SwaggerOptions| {
+            let project = parse_swagger(swagger.as_bytes(), options).unwrap();
+            let mut tags = project.apis.keys().cloned().collect::<Vec<String>>();
+            tags.sort();
+            tags
+        };
+
+        let mut options = SwaggerOptions {
+            exclude_tags: vec!["内部".to_string()],
+            ..SwaggerOptions::default()
+        };
+        assert_eq!(tags(&options), vec!["用户", "订单"]);
+        options.include_tags = vec!["内部".to_string(), "公开".to_string()];
+        assert_eq!(tags(&options), vec!["内部"]);
+
+        // 不存在的分组给出警告
+        let (_, diagnostics) = parse_swagger_with_diagnostics(swagger.as_bytes(), &options);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|item| item.message.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["要过滤的分组公开不存在"]
+        );
+    }
+
+    #[test]
+    fn document_validation_constraints() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "订单"}],
+            "paths": {"/orders": {"post": {
+                "tags": ["订单"], "operationId": "create", "produces": [], "responses": {},
+                "parameters": [
+                    {"name": "remark", "in": "query", "required": false, "type": "string",
+                     "allowEmptyValue": true},
+                    {"name": "amount", "in": "query", "required": true, "type": "number",
+                     "multipleOf": 0.01},
+                    {"name": "order", "in": "body", "required": true,
+                     "schema": {"$ref": "#/definitions/Order", "originalRef": "Order"}}
+                ]
+            }}},
+            "securityDefinitions": {},
+            "definitions": {"Order": {"type": "object", "properties": {
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true},
+                "count": {"type": "integer", "multipleOf": 5},
+                "no": {"type": "string"}
+            }}}
+        })
+        .to_string();
+        let project = parse_swagger(swagger.as_bytes(), &SwaggerOptions::default()).unwrap();
+        let constraints = project.apis["订单"][0]
+            .query_params
+            .iter()
+            .map(|item| (item.name.as_str(), item.constraints.as_str()))
+            .collect::<HashMap<&str, &str>>();
+        assert_eq!(constraints["remark"], "允许空值");
+        assert_eq!(constraints["amount"], "必须是0.01的倍数");
+        assert_eq!(constraints["body.tags"], "元素不能重复");
+        assert_eq!(constraints["body.count"], "必须是5的倍数");
+        assert_eq!(constraints["body.no"], "");
+    }
+
+    #[test]
+    fn export_schemas_keeps_circular_refs() {
+        let swagger = serde_json::json!({
+            "definitions": {
+                "Dept": {"type": "object", "properties": {
+                    "parent": {"$ref": "#/definitions/Dept", "originalRef": "Dept"},
+                    "users": {"type": "array",
+                        "items": {"$ref": "#/definitions/User", "originalRef": "User"}}
+                }},
+                "User": {"type": "object", "properties": {
+                    "dept": {"$ref": "#/definitions/Dept", "originalRef": "Dept"}
+                }},
+                "Tag": {"type": "object", "properties": {"name": {"type": "string"}}}
+            }
+        })
+        .to_string();
+        let schemas = export_json_schemas(swagger.as_bytes())
+            .unwrap()
+            .into_iter()
+            .collect::<HashMap<String, Value>>();
+
+        let user = &schemas["User"];
+        assert_eq!(user["title"], "User");
+        assert_eq!(user["properties"]["dept"]["$ref"], "#/definitions/Dept");
+        assert!(user["properties"]["dept"].get("originalRef").is_none());
+        let mut names = user["definitions"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<String>>();
+        names.sort();
+        assert_eq!(names, vec!["Dept", "User"]);
+        assert_eq!(
+            user["definitions"]["Dept"]["properties"]["parent"]["$ref"],
+            "#/definitions/Dept"
+        );
+        assert!(schemas["Tag"].get("definitions").is_none());
+    }
+
+    #[test]
+    fn array_body_expands_items() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "订单"}],
+            "paths": {"/lines/batch": {"post": {
+                "tags": ["订单"], "operationId": "batchCreate", "produces": [],
+                "responses": {},
+                "parameters": [{"name": "lines", "in": "body", "required": true,
+                    "schema": {"type": "array",
+                        "items": {"$ref": "#/definitions/Line", "originalRef": "Line"}}}]
+            }}},
+            "securityDefinitions": {},
+            "definitions": test_definitions()
+        })
+        .to_string();
+        let options = SwaggerOptions {
+            flatten_style: FlattenStyle::Dot,
+            ..SwaggerOptions::default()
+        };
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        let api = &project.apis["订单"][0];
+        let names = api
+            .query_params
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["lines", "body.[].sku"]);
+        assert!(api.example_curl.ends_with("-d '[{\"sku\":\"string\"}]'"));
+    }
+
+    #[test]
+    fn collect_parse_diagnostics() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "订单"}],
+            "paths": {"/orders": {
+                "get": {"tags": ["订单", "报表"], "operationId": "list", "responses": {},
+                    "parameters": [{"$ref": "#/parameters/page"}]},
+                "post": {"operationId": "create", "responses": {}}
+            }},
+            "securityDefinitions": {},
+            "definitions": {
+                "Order": {"type": "object", "properties": {
+                    "user": {"$ref": "#/definitions/User", "originalRef": "User"}
+                }}
+            }
+        })
+        .to_string();
+        let (project, diagnostics) =
+            parse_swagger_with_diagnostics(swagger.as_bytes(), &SwaggerOptions::default());
+        assert_eq!(project.endpoint_count(), 1);
+        let messages = diagnostics
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            messages,
+            vec![
+                "[警告] GET /orders: 分组报表没有在tags中声明，接口不会出现在该分组",
+                "[警告] GET /orders: 参数引用#/parameters/page不存在，已忽略",
+                "[警告] POST /orders: 接口没有分组，不会出现在文档中",
+                "[警告] definitions/Order: 引用的定义User不存在，相关字段不会展开",
+            ]
+        );
+
+        // 无法解析时返回空的模型
+        let (project, diagnostics) =
+            parse_swagger_with_diagnostics(b"{", &SwaggerOptions::default());
+        assert_eq!(project.endpoint_count(), 0);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn inherit_global_content_types() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "produces": ["application/json"],
+            "consumes": ["application/json"],
+            "tags": [{"name": "订单"}],
+            "paths": {
+                "/orders": {"post": {"tags": ["订单"], "operationId": "create", "responses": {}}},
+                "/orders/import": {"post": {"tags": ["订单"], "operationId": "import",
+                    "consumes": ["multipart/form-data"], "produces": ["text/plain"],
+                    "responses": {}}}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let project = parse_swagger(swagger.as_bytes(), &SwaggerOptions::default()).unwrap();
+        let types = project.apis["订单"]
+            .iter()
+            .map(|api| (api.api_type.as_str(), api.return_type.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(
+            types,
+            vec![
+                ("application/json", "application/json"),
+                ("multipart/form-data", "text/plain")
+            ]
+        );
+    }
+
+    #[test]
+    fn document_external_docs() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "externalDocs": {"url": "https://docs.example.com"},
+            "tags": [{"name": "订单"}],
+            "paths": {
+                "/orders": {"get": {"tags": ["订单"], "operationId": "list", "summary": "订单列表",
+                    "externalDocs": {"description": "分页说明", "url": "https://docs.example.com/page"},
+                    "responses": {}}},
+                "/orders/{id}": {"get": {"tags": ["订单"], "operationId": "get", "summary": "订单详情",
+                    "responses": {}}}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let options = SwaggerOptions {
+            timestamp: false,
+            ..SwaggerOptions::default()
+        };
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        assert_eq!(project.external_docs, "https://docs.example.com");
+        let apis = &project.apis["订单"];
+        assert_eq!(
+            apis[0].external_docs,
+            "分页说明 https://docs.example.com/page"
+        );
+        assert_eq!(apis[1].external_docs, "");
+
+        let docx = render_swagger_docx(&project, "zh").unwrap();
+        let text = crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap());
+        assert!(text.contains("参考文档：https://docs.example.com"));
+        assert!(text.contains("订单列表（参考文档：分页说明 https://docs.example.com/page）"));
+        assert_eq!(text.matches("参考文档").count(), 2);
+    }
+
+    #[test]
+    fn range_status_codes() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "订单"}],
+            "paths": {"/orders": {"get": {
+                "tags": ["订单"], "operationId": "list", "produces": [],
+                "responses": {
+                    "default": {"description": "错误"},
+                    "4XX": {"description": "请求错误"},
+                    "404": {"description": "不存在"},
+                    "2xx": {"description": "OK",
+                        "schema": {"$ref": "#/definitions/Line", "originalRef": "Line"}}
+                }
+            }}},
+            "securityDefinitions": {},
+            "definitions": test_definitions()
+        })
+        .to_string();
+        let project = parse_swagger(swagger.as_bytes(), &SwaggerOptions::default()).unwrap();
+        let api = &project.apis["订单"][0];
+        let codes = api
+            .status_codes
+            .iter()
+            .map(|item| (item.code.as_str(), item.explain.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(
+            codes,
+            vec![
+                ("2xx", "成功"),
+                ("404", ""),
+                ("4XX", "客户端错误"),
+                ("default", "默认/其他")
+            ]
+        );
+
+        // 没有200时使用2XX的响应
+        assert_eq!(api.return_params[0].name, "body.sku");
+        assert!(api.return_params_example.contains("\"sku\""));
+    }
+
+    #[test]
+    fn free_form_object_is_opaque_leaf() {
+        let definitions: HashMap<String, Definition> = serde_json::from_value(serde_json::json!({
+            "Event": {"type": "object", "properties": {
+                "payload": {"type": "object", "description": "事件内容"},
+                "meta": {"type": "object", "properties": {"id": {"type": "string"}}}
+            }}
+        }))
+        .unwrap();
+        let options = SwaggerOptions::default();
+        let event = "Event".to_string();
+
+        let mut returns =
+            response_by_definitions(&event, &definitions, &mut HashSet::new(), 1, &options)
+                .into_iter()
+                .map(|item| (item.name, item.data_type))
+                .collect::<Vec<(String, String)>>();
+        returns.sort();
+        assert_eq!(
+            returns,
+            vec![
+                ("meta".to_string(), "object".to_string()),
+                ("payload".to_string(), "object (任意)".to_string()),
+            ]
+        );
+
+        let params = param_by_definitions(&event, &definitions, &mut HashSet::new(), 1, &options);
+        let payload = params.iter().find(|item| item.name == "payload").unwrap();
+        assert_eq!(payload.data_type, "object (任意)");
+        assert_eq!(payload.example, "{}");
+
+        let mut example = Value::Object(Map::new());
+        fill_value_by_definitions(
+            &event,
+            &mut example,
+            &definitions,
+            &mut HashSet::new(),
+            1,
+            &options,
+        );
+        assert_eq!(example["payload"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn document_map_fields() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "订单服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "订单"}],
+            "paths": {"/orders/stats": {"get": {
+                "tags": ["订单"], "operationId": "stats", "produces": [],
+                "responses": {"200": {"description": "OK",
+                    "schema": {"$ref": "#/definitions/Stats", "originalRef": "Stats"}}}
+            }}},
+            "securityDefinitions": {},
+            "definitions": {
+                "Stats": {"type": "object", "properties": {
+                    "counts": {"type": "object", "additionalProperties": {"type": "integer"}},
+                    "lines": {"type": "object",
+                        "additionalProperties": {"$ref": "#/definitions/Line", "originalRef": "Line"}},
+                    "extra": {"type": "object", "additionalProperties": true}
+                }},
+                "Line": {"type": "object", "properties": {"sku": {"type": "string"}}}
+            }
+        })
+        .to_string();
+        let project = parse_swagger(swagger.as_bytes(), &SwaggerOptions::default()).unwrap();
+        let api = &project.apis["订单"][0];
+        let mut params = api
+            .return_params
+            .iter()
+            .map(|item| (item.name.as_str(), item.data_type.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        params.sort();
+        assert_eq!(
+            params,
+            vec![
+                ("body.counts", "map<string, integer>"),
+                ("body.extra", "map<string, object>"),
+                ("body.lines", "map<string, Line>"),
+            ]
+        );
+
+        let example: Value = serde_json::from_str(&api.return_params_example).unwrap();
+        assert_eq!(
+            example,
+            serde_json::json!({
+                "counts": {"key": 1},
+                "lines": {"key": {"sku": "string"}},
+                "extra": {"key": {}}
+            })
+        );
+    }
+
+    #[test]
+    fn fill_empty_values_with_placeholder() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "用户服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "用户"}],
+            "paths": {
+                "/users": {"get": {"tags": ["用户"], "operationId": "list", "produces": [],
+                    "parameters": [{"name": "id", "in": "query", "required": false}],
+                    "responses": {"200": {"description": ""}}}}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let options = SwaggerOptions {
+            empty_placeholder: Some("-".to_string()),
+            ..SwaggerOptions::default()
+        };
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        let api = &project.apis["用户"][0];
+        assert_eq!(api.desc, "-");
+        assert_eq!(api.auth, "-");
+        assert_eq!(api.query_params[0].data_type, "-");
+        assert_eq!(api.query_params[0].desc, "-");
+        assert_eq!(api.status_codes[0].desc, "-");
+
+        // 名称等标识字段不受影响
+        assert_eq!(api.name, "");
+        assert_eq!(api.query_params[0].name, "id");
+    }
+
+    #[test]
+    fn number_tags_and_endpoints() {
+        let operation = |summary: &str, tag: &str| {
+            serde_json::json!({"tags": [tag], "summary": summary, "operationId": summary,
+                "produces": [], "responses": {}})
+        };
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "用户服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "用户"}, {"name": "订单"}],
+            "paths": {
+                "/users": {
+                    "post": operation("创建用户", "用户"),
+                    "get": operation("查询用户", "用户")
+                },
+                "/orders": {"get": operation("查询订单", "订单")}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let options = SwaggerOptions {
+            number_sections: true,
+            ..SwaggerOptions::default()
+        };
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+
+        // 分组按名称排序："用户"(U+7528)在"订单"(U+8BA2)之前
+        assert_eq!(project.tag_numbers["用户"], "1");
+        assert_eq!(project.tag_numbers["订单"], "2");
+        let numbers = project.apis["用户"]
+            .iter()
+            .map(|api| (api.number.as_str(), api.name.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(numbers, vec![("1.1", "查询用户"), ("1.2", "创建用户")]);
+
+        let docx = render_swagger_docx(&project, "zh").unwrap();
+        let text = crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap());
+        assert!(text.contains("1 用户\n"));
+        assert!(text.contains("1.2 创建用户\n"));
+        assert!(text.contains("2.1 查询订单\n"));
+
+        // 未开启时不编号
+        let project = parse_swagger(swagger.as_bytes(), &SwaggerOptions::default()).unwrap();
+        assert!(project.tag_numbers.is_empty());
+        assert_eq!(project.apis["订单"][0].number, "");
+    }
+
+    #[test]
+    fn record_source_and_generated_at() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "用户服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [],
+            "paths": {},
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let mut options = SwaggerOptions {
+            source: Some("api/swagger.json".to_string()),
+            ..SwaggerOptions::default()
+        };
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        assert_eq!(project.source, "api/swagger.json");
+        assert!(chrono::DateTime::parse_from_rfc3339(&project.generated_at).is_ok());
+
+        // 关闭时间戳后两次生成的文档内容相同
+        options.timestamp = false;
+        let project = parse_swagger(swagger.as_bytes(), &options).unwrap();
+        assert_eq!(project.generated_at, "");
+        let render_text = || {
+            let docx = swagger_to_docx(swagger.as_bytes(), &options).unwrap();
+            crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap())
+        };
+        let text = render_text();
+        assert_eq!(text, render_text());
+        assert!(text.contains("来源：api/swagger.json"));
+        assert!(!text.contains("生成时间"));
+    }
+
+    #[test]
+    fn resolve_shared_parameter_refs() {
+        let swagger = serde_json::json!({
+            "swagger": "2.0",
+            "info": {"title": "用户服务", "version": "1.0"},
+            "host": "api.example.com",
+            "tags": [{"name": "用户"}],
+            "parameters": {
+                "pageSize": {"name": "size", "in": "query", "type": "integer",
+                    "description": "每页条数", "required": false}
+            },
+            "paths": {
+                "/users": {"get": {"tags": ["用户"], "operationId": "list", "produces": [],
+                    "parameters": [
+                        {"$ref": "#/parameters/pageSize", "required": true},
+                        {"$ref": "#/parameters/missing"},
+                        {"name": "name", "in": "query", "type": "string", "required": false}
+                    ],
+                    "responses": {}}}
+            },
+            "securityDefinitions": {},
+            "definitions": {}
+        })
+        .to_string();
+        let project = parse_swagger(swagger.as_bytes(), &SwaggerOptions::default()).unwrap();
+        let params = project.apis["用户"][0]
+            .query_params
+            .iter()
+            .map(|item| {
+                (
+                    item.name.as_str(),
+                    item.data_type.as_str(),
+                    item.required.as_str(),
+                    item.desc.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            params,
+            vec![
+                ("size", "integer", "Y", "每页条数"),
+                ("name", "string", "N", "")
+            ]
+        );
+    }
+
+    #[test]
+    fn models_list_direct_fields() {
+        let models = model_infos(&test_definitions());
+        let names = models
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["Line", "Order", "Page"]);
+
+        // 引用的模型不展开，只显示类型名
+        let fields = models[1]
+            .fields
+            .iter()
+            .map(|item| (item.name.as_str(), item.data_type.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("lines", "array<Line>"),
+                ("no", "string"),
+                ("tags", "array<string>")
+            ]
+        );
+
+        let docx = render_swagger_docx(
+            &DocxProjectInfo::new("订单服务").add_model(
+                DocxModelInfo::new("Line")
+                    .desc("订单明细")
+                    .add_field(DocxParamInfo::new("sku", "string").required(true)),
+            ),
+            "zh",
+        )
+        .unwrap();
+        let text = crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap());
+        assert!(text.contains("附录：数据模型"));
+        assert!(text.contains("Line\n订单明细\n"));
+        assert!(text.contains("sku\tstring\tY"));
+    }
+
+    #[test]
+    fn render_project_built_without_swagger() {
+        let api = DocxApiInfo::new("查询用户", "get", "/users/{id}")
+            .add_query_param(
+                DocxParamInfo::new("id", "integer")
+                    .param_type("path")
+                    .required(true)
+                    .desc("用户id")
+                    .example("1")
+                    .constraints("必须是1的倍数"),
+            )
+            .add_status_code(DocxStatusCode::new("200", "OK"))
+            .add_return_param(DocxReturnParamInfo::new("name", "string").desc("用户名"));
+        let project = DocxProjectInfo::new("用户服务")
+            .add_api(
+                "用户",
+                api.clone()
+                    .auth("token（apiKey，header参数 Authorization）"),
+            )
+            .add_api("订单", api)
+            .tag_desc("用户", "用户的增删改查");
+
+        assert_eq!(project.apis["用户"][0].query_params[0].required, "Y");
+        let docx = render_swagger_docx(&project, "zh").unwrap();
+        assert!(docx.starts_with(b"PK"));
+
+        // 只有设置了描述的分组才会多出介绍段落
+        let text = crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap());
+        assert_eq!(text.matches("用户的增删改查").count(), 1);
+        assert!(text.contains("用户id（必须是1的倍数）（示例：1）"));
+        assert!(text.contains("用户\n用户的增删改查\n"));
+        assert!(text.contains("认证\ttoken（apiKey，header参数 Authorization）"));
+        assert!(text.contains("认证\t无"));
+        assert!(!text.contains("附录"));
+        assert!(!text.contains("版本"));
+    }
+
+    #[test]
+    fn export_endpoints_as_csv() {
+        let project = DocxProjectInfo::new("用户服务")
+            .add_api(
+                "用户",
+                DocxApiInfo::new("查询用户, 按id", "get", "/users/{id}")
+                    .auth("token\n\"Bearer\"")
+                    .add_query_param(DocxParamInfo::new("id", "integer"))
+                    .add_status_code(DocxStatusCode::new("200", "OK"))
+                    .add_status_code(DocxStatusCode::new("404", "Not Found")),
+            )
+            .add_api("订单", DocxApiInfo::new("订单列表", "get", "/orders"));
+        assert_eq!(
+            project_to_csv(&project),
+            "tag,method,url,name,auth,param_count,status_codes\r\n\
+             用户,get,/users/{id},\"查询用户, 按id\",\"token\n\"\"Bearer\"\"\",1,200 404\r\n\
+             订单,get,/orders,订单列表,,0,\r\n"
+        );
+    }
+
+    #[test]
+    fn render_cover_page() {
+        // 1x1的png
+        let logo = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+        let project = DocxProjectInfo::new("用户服务")
+            .version("1.2.0")
+            .cover(logo)
+            .add_api("用户", DocxApiInfo::new("查询用户", "get", "/users"));
+        let docx = render_swagger_docx(&project, "zh").unwrap();
+
+        // 封面和正文各有一个标题
+        let text = crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap());
+        assert!(text.contains("版本：1.2.0"));
+        assert_eq!(text.matches("用户服务").count(), 2);
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx)).unwrap();
+        assert!(
+            archive
+                .file_names()
+                .any(|name| name.starts_with("word/media/"))
+        );
+        let mut document = String::new();
+        std::io::Read::read_to_string(
+            &mut archive.by_name("word/document.xml").unwrap(),
+            &mut document,
+        )
+        .unwrap();
+        assert!(document.contains(r#"<w:br w:type="page"/>"#));
+    }
+
+    #[test]
+    fn render_schema_tree_line_by_line() {
+        let api = DocxApiInfo {
+            schema_tree: "返回 body\n  no: string".to_string(),
+            ..DocxApiInfo::new("查询订单", "get", "/orders")
+        };
+        let project = DocxProjectInfo::new("订单服务").add_api("订单", api);
+        for (lang, label) in [("zh", "数据结构"), ("en", "Schema")] {
+            let docx = render_swagger_docx(&project, lang).unwrap();
+            let text = crate::docx_to_html::extract_text(&docx_rs::read_docx(&docx).unwrap());
+            assert!(text.contains(label));
+
+            // 每行一个换行，保留缩进
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx)).unwrap();
+            let mut document = String::new();
+            std::io::Read::read_to_string(
+                &mut archive.by_name("word/document.xml").unwrap(),
+                &mut document,
+            )
+            .unwrap();
+            assert!(
+                document.contains(
+                    r#"返回 body</w:t><w:br/><w:t xml:space="preserve">  no: string</w:t>"#
+                )
+            );
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// 按修改时间轮询监听输入文件，没有使用notify等文件系统事件
+// 轮询不需要额外依赖，在网络盘、docker挂载目录等收不到事件的场景也能工作，代价是最多延迟一个轮询间隔
+use std::{
+    collections::HashMap,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+// 轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// 防抖时间，文件在这段时间内没有再变化才触发
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// 监听本地文件的修改时间，变化后调用on_change，不会返回
+pub fn watch_files(paths: &[String], mut on_change: impl FnMut()) {
+    // 网络文件无法监听
+    let paths: Vec<&String> = paths
+        .iter()
+        .filter(|path| !path.starts_with("http"))
+        .collect();
+    let mut last_modified = modified_times(&paths);
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let mut current = modified_times(&paths);
+        if current == last_modified {
+            continue;
+        }
+
+        // 等待连续的修改结束
+        loop {
+            thread::sleep(DEBOUNCE);
+            let next = modified_times(&paths);
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+
+        last_modified = current;
+        on_change();
+    }
+}
+
+fn modified_times(paths: &[&String]) -> HashMap<String, Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            let modified = std::fs::metadata(path)
+                .and_then(|item| item.modified())
+                .ok();
+            (path.to_string(), modified)
+        })
+        .collect()
+}